@@ -3,6 +3,18 @@ use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
 use std::fmt::Debug;
 
+/// Re-exports of the traits and types that are commonly needed to manage conditions.
+pub mod prelude {
+    pub use crate::{
+        Condition,
+        ConditionAccessor,
+        ConditionManager,
+        ConditionStatus,
+        ConditionType,
+        Conditions,
+    };
+}
+
 /// Enums that implement [`ConditionType`] can be used to differentiate [`Condition`]
 /// and describe the state of the resource.
 pub trait ConditionType: Default + Debug + EnumSetType {
@@ -12,14 +24,30 @@ pub trait ConditionType: Default + Debug + EnumSetType {
     /// Variants that must be true to consider the happy condition true.
     fn dependents() -> EnumSet<Self>;
 
-    /// Whether the [`ConditionType`] determines happiness.
+    /// Variants that must be true to consider this variant true, for a dependent that rolls up
+    /// its own sub-conditions. Only the happy condition has dependents by default.
+    #[inline]
+    fn dependents_of(&self) -> EnumSet<Self> {
+        if *self == Self::happy() {
+            Self::dependents()
+        } else {
+            EnumSet::empty()
+        }
+    }
+
+    /// Whether the [`ConditionType`] determines happiness, directly or through the dependent
+    /// that it rolls up into.
     #[inline]
     fn is_terminal(&self) -> bool {
-        Self::dependents().contains(*self) || *self == Self::happy()
+        Self::dependents().contains(*self)
+            || *self == Self::happy()
+            || EnumSet::<Self>::all().iter().any(|c| c.dependents_of().contains(*self))
     }
 
     /// A [`Condition`] severity defaults to whether it determines overall resource readiness or
     /// not.
+    ///
+    /// A dependent with [`ConditionSeverity::Warning`] is surfaced without blocking readiness.
     #[inline]
     fn severity(&self) -> ConditionSeverity {
         if self.is_terminal() {
@@ -28,6 +56,19 @@ pub trait ConditionType: Default + Debug + EnumSetType {
             ConditionSeverity::Info
         }
     }
+
+    /// The name of the variant as it is serialized, which orders [`Conditions`] and names the type
+    /// of a [`Metav1Condition`]. Defaults to the [`Debug`] name; the derive follows serde renames.
+    #[inline]
+    fn serialized_name(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    /// Whether the [`ConditionType`] is a dependent that blocks happiness when not true.
+    #[inline]
+    fn blocks_happiness(&self) -> bool {
+        Self::dependents().contains(*self) && self.severity() != ConditionSeverity::Warning
+    }
 }
 
 /// Provides [`ConditionManager`] access to the [`Conditions`],
@@ -46,6 +87,13 @@ pub trait ConditionAccessor<C: ConditionType> {
         self.manager().is_happy()
     }
 
+    /// Mark the top level condition true. It only becomes true when every dependent is happy;
+    /// otherwise it takes on the status, reason and message of the most unhappy dependent, or is
+    /// unknown while a dependent has not been initialized.
+    fn mark_ready(&mut self) {
+        self.manager().mark_true(C::happy());
+    }
+
     /// Set the status of the top level condition type to false
     fn mark_false(&mut self, reason: &str, message: Option<String>) {
         let t = self.manager().get_top_level_condition().type_;
@@ -77,15 +125,28 @@ pub enum ConditionStatus {
     Unknown,
 }
 
+impl std::fmt::Display for ConditionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            ConditionStatus::True => "True",
+            ConditionStatus::False => "False",
+            ConditionStatus::Unknown => "Unknown",
+        };
+        f.write_str(status)
+    }
+}
+
 impl Default for ConditionStatus {
     fn default() -> Self {
         ConditionStatus::Unknown
     }
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug, JsonSchema, PartialEq)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, JsonSchema, PartialEq, Eq)]
 #[non_exhaustive]
 /// The importance of a conditions status.
+///
+/// Severities are ordered by importance, such that `Error > Warning > Info`.
 pub enum ConditionSeverity {
     Error,
     Warning,
@@ -102,6 +163,93 @@ impl ConditionSeverity {
     pub fn is_err(&self) -> bool {
         *self == ConditionSeverity::Error
     }
+
+    fn rank(&self) -> u8 {
+        match self {
+            ConditionSeverity::Info => 0,
+            ConditionSeverity::Warning => 1,
+            ConditionSeverity::Error => 2,
+        }
+    }
+}
+
+impl PartialOrd for ConditionSeverity {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ConditionSeverity {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+/// A time that is excluded from semantic equality, such that two [`Condition`]s that differ only
+/// in their transition time compare equal. Serializes as an RFC 3339 timestamp.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(transparent)]
+pub struct VolatileTime(pub chrono::DateTime<chrono::Utc>);
+
+impl VolatileTime {
+    pub fn now() -> Self {
+        VolatileTime(chrono::Utc::now())
+    }
+
+    /// Deserialize from either an RFC 3339 timestamp or the epoch seconds stored by older
+    /// objects. Empty strings deserialize as `None`.
+    fn deserialize_opt<'de, D>(deserializer: D) -> Result<Option<VolatileTime>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use chrono::TimeZone;
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Time {
+            Epoch(i64),
+            Text(String),
+        }
+
+        match Option::<Time>::deserialize(deserializer)? {
+            None => Ok(None),
+            Some(Time::Text(text)) if text.is_empty() => Ok(None),
+            Some(Time::Text(text)) => chrono::DateTime::parse_from_rfc3339(&text)
+                .map(|time| Some(VolatileTime(time.with_timezone(&chrono::Utc))))
+                .map_err(D::Error::custom),
+            Some(Time::Epoch(secs)) => chrono::Utc.timestamp_opt(secs, 0)
+                .single()
+                .map(|time| Some(VolatileTime(time)))
+                .ok_or_else(|| D::Error::custom(format!("epoch seconds out of range: {secs}"))),
+        }
+    }
+}
+
+impl From<chrono::DateTime<chrono::Utc>> for VolatileTime {
+    fn from(time: chrono::DateTime<chrono::Utc>) -> Self {
+        VolatileTime(time)
+    }
+}
+
+impl PartialEq for VolatileTime {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl JsonSchema for VolatileTime {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        <chrono::DateTime<chrono::Utc>>::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        <chrono::DateTime<chrono::Utc>>::json_schema(gen)
+    }
 }
 
 /// A custom resource status condition.
@@ -118,11 +266,17 @@ pub struct Condition<C: ConditionType> {
     #[serde(default)]
     #[serde(skip_serializing_if = "ConditionSeverity::is_err")]
     pub severity: ConditionSeverity,
-    // TODO: make this a "VolatileTime"
-    //#[serde(deserialize_with = "from_ts")]
-    pub last_transition_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, deserialize_with = "VolatileTime::deserialize_opt")]
+    pub last_transition_time: Option<VolatileTime>,
     pub reason: Option<String>,
     pub message: Option<String>,
+    /// Whether a false condition is a permanent failure, such as a reference to a kind that does
+    /// not exist in the cluster, which will not be resolved by retrying until the spec changes.
+    ///
+    /// This is an extension to the Knative condition. It is only serialized when set, so that
+    /// other conditions round-trip unchanged.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub permanent: bool,
 }
 
 impl<C: ConditionType> Default for Condition<C> {
@@ -131,9 +285,10 @@ impl<C: ConditionType> Default for Condition<C> {
             type_: C::default(),
             status: ConditionStatus::default(),
             severity: ConditionSeverity::default(),
-            last_transition_time: Some(chrono::Utc::now()),
+            last_transition_time: Some(VolatileTime::now()),
             reason: None,
-            message: None
+            message: None,
+            permanent: false,
         }
     }
 }
@@ -144,7 +299,7 @@ impl<C: ConditionType> PartialOrd for Condition<C> {
         use std::cmp::Ordering;
 
         let time_ord = match (self.last_transition_time, other.last_transition_time) {
-            (Some(left), Some(right)) => left.partial_cmp(&right),
+            (Some(left), Some(right)) => left.0.partial_cmp(&right.0),
             _ => None
         };
 
@@ -163,6 +318,7 @@ impl<C: ConditionType> Condition<C> {
     pub fn new(type_: C) -> Self {
         Condition {
             type_,
+            severity: type_.severity(),
             ..Default::default()
         }
     }
@@ -185,10 +341,102 @@ impl<C: ConditionType> Condition<C> {
     pub fn is_unknown(&self) -> bool {
         self.status == ConditionStatus::Unknown
     }
+
+    /// Convert into the shape of a Kubernetes `metav1.Condition`.
+    ///
+    /// `reason` defaults to the name of the [`ConditionType`] when absent, and
+    /// `last_transition_time` defaults to now.
+    pub fn to_metav1(&self) -> Metav1Condition {
+        let type_ = self.type_.serialized_name();
+        Metav1Condition {
+            reason: self.reason.clone().unwrap_or_else(|| type_.clone()),
+            type_,
+            status: self.status.to_string(),
+            observed_generation: None,
+            last_transition_time: self.last_transition_time.map_or_else(chrono::Utc::now, |t| t.0),
+            message: self.message.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Convert from the shape of a Kubernetes `metav1.Condition`.
+    ///
+    /// Returns `None` if the type or status do not match a known variant.
+    pub fn from_metav1(condition: &Metav1Condition) -> Option<Condition<C>> {
+        let type_ = EnumSet::<C>::all()
+            .iter()
+            .find(|c| c.serialized_name() == condition.type_)?;
+        let status = match condition.status.as_str() {
+            "True" => ConditionStatus::True,
+            "False" => ConditionStatus::False,
+            "Unknown" => ConditionStatus::Unknown,
+            _ => return None,
+        };
+        Some(Condition {
+            type_,
+            status,
+            severity: type_.severity(),
+            last_transition_time: Some(condition.last_transition_time.into()),
+            reason: Some(condition.reason.clone()).filter(|r| !r.is_empty()),
+            message: Some(condition.message.clone()).filter(|m| !m.is_empty()),
+            permanent: false,
+        })
+    }
+}
+
+/// A condition in the shape of the core Kubernetes `metav1.Condition`, for interop with
+/// controllers and tooling that expect the standard type.
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Metav1Condition {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_generation: Option<i64>,
+    pub last_transition_time: chrono::DateTime<chrono::Utc>,
+    pub reason: String,
+    #[serde(default)]
+    pub message: String,
+}
+
+/// A [`Condition`] without its empty fields, as serialized by [`Conditions::canonical`].
+#[derive(Serialize)]
+struct CanonicalCondition<'a, C: ConditionType> {
+    #[serde(rename = "type")]
+    type_: C,
+    status: ConditionStatus,
+    #[serde(skip_serializing_if = "ConditionSeverity::is_err")]
+    severity: ConditionSeverity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_transition_time: Option<VolatileTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    permanent: bool,
+}
+
+impl<'a, C: ConditionType> From<&'a Condition<C>> for CanonicalCondition<'a, C> {
+    fn from(condition: &'a Condition<C>) -> Self {
+        CanonicalCondition {
+            type_: condition.type_,
+            status: condition.status,
+            severity: condition.severity,
+            last_transition_time: condition.last_transition_time,
+            reason: condition.reason.as_deref(),
+            message: condition.message.as_deref(),
+            permanent: condition.permanent,
+        }
+    }
 }
 
 /// A `Vec<Condition>` that maintains transition times.
+///
+/// Deserializes as with [`Conditions::from_partial`], so that a status written by another
+/// controller without the happy condition remains manageable.
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq)]
+#[serde(try_from = "Vec<Condition<C>>")]
 pub struct Conditions<C: ConditionType>(Vec<Condition<C>>);
 
 impl<C: ConditionType> Default for Conditions<C> {
@@ -197,7 +445,41 @@ impl<C: ConditionType> Default for Conditions<C> {
             .into_iter()
             .chain(C::dependents())
             .map(Condition::new);
-        Conditions(Vec::from_iter(iter))
+        let mut conditions = Conditions(Vec::from_iter(iter));
+        conditions.sort();
+        conditions
+    }
+}
+
+impl<C: ConditionType> FromIterator<Condition<C>> for Conditions<C> {
+    /// Collect conditions as with [`Conditions::from_partial`], inserting the happy condition as
+    /// unknown if absent.
+    ///
+    /// # Panic
+    /// Panics if a [`ConditionType`] is collected more than once.
+    fn from_iter<I: IntoIterator<Item = Condition<C>>>(iter: I) -> Self {
+        Conditions::from_partial(iter.into_iter().collect())
+    }
+}
+
+impl<C: ConditionType> TryFrom<Vec<Condition<C>>> for Conditions<C> {
+    type Error = String;
+
+    fn try_from(conditions: Vec<Condition<C>>) -> Result<Self, Self::Error> {
+        let unique = EnumSet::from_iter(conditions.iter().map(|c| c.type_));
+        match unique.len() == conditions.len() {
+            true => Ok(Conditions::from_partial(conditions)),
+            false => Err(format!("duplicate condition type in {:?}", unique)),
+        }
+    }
+}
+
+impl<C: ConditionType> IntoIterator for Conditions<C> {
+    type Item = Condition<C>;
+    type IntoIter = std::vec::IntoIter<Condition<C>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
     }
 }
 
@@ -215,6 +497,79 @@ impl<C: ConditionType> Conditions<C> {
         Conditions(conditions)
     }
 
+    /// Initialize from any subset of conditions, such as a status whose dependents have not yet
+    /// been evaluated. The happy condition is inserted as unknown if absent, and the conditions
+    /// are sorted as though they had been set.
+    pub fn from_partial(mut conditions: Vec<Condition<C>>) -> Conditions<C> {
+        if !conditions.iter().any(|c| c.type_ == C::happy()) {
+            conditions.insert(0, Condition::new(C::happy()));
+        }
+        let mut conditions = Conditions::with_conditions(conditions);
+        conditions.sort();
+        conditions
+    }
+
+    /// Copy in each condition of `previous` that is absent from these conditions, preserving its
+    /// transition time. Conditions that are already set are not overwritten.
+    pub fn merge_from(&mut self, previous: &Conditions<C>) {
+        for condition in &previous.0 {
+            if self.get_cond(&condition.type_).is_none() {
+                self.0.push(condition.clone());
+            }
+        }
+        self.sort();
+    }
+
+    /// A canonical form of the conditions for change detection, such as computing status diffs
+    /// or hashes, in whichever format it is serialized to. Conditions are sorted by the serialized
+    /// name of their [`ConditionType`] and empty fields are omitted, so that the form does not
+    /// depend on insertion order.
+    pub fn canonical(&self) -> impl Serialize + '_
+    where
+        C: Serialize,
+    {
+        let mut conditions = self.0.iter().map(CanonicalCondition::from).collect::<Vec<_>>();
+        conditions.sort_by_cached_key(|c| c.type_.serialized_name());
+        conditions
+    }
+
+    /// Whether any condition is a permanent failure, so that reconciling should not be retried
+    /// until the spec of the resource changes.
+    ///
+    /// See [`ConditionManager::mark_false_permanent`].
+    pub fn has_permanent_failure(&self) -> bool {
+        self.0.iter().any(|c| c.permanent && c.is_false())
+    }
+
+    /// Returns the condition of `type_`, for read-only access without a [`ConditionManager`].
+    pub fn get(&self, type_: &C) -> Option<&Condition<C>> {
+        self.get_cond(type_)
+    }
+
+    /// Returns the happy [`Condition`].
+    ///
+    /// # Panic
+    /// Panics if the [`Conditions`] have not been properly initialized.
+    /// See [`Conditions::default()`].
+    pub fn get_top_level_condition(&self) -> &Condition<C> {
+        self.get_cond(&C::happy())
+            .expect("top level condition is initialized")
+    }
+
+    /// Whether the happy condition is true.
+    pub fn is_happy(&self) -> bool {
+        self.get_top_level_condition().is_true()
+    }
+
+    /// Returns the conditions that have not transitioned within `older_than`, such as a dependent
+    /// that has been unknown for too long. Conditions without a transition time are skipped.
+    pub fn stale(&self, older_than: chrono::Duration) -> Vec<&Condition<C>> {
+        let cutoff = chrono::Utc::now() - older_than;
+        self.0.iter()
+            .filter(|c| matches!(c.last_transition_time, Some(time) if time.0 < cutoff))
+            .collect()
+    }
+
     fn get_cond(&self, type_: &C) -> Option<&Condition<C>> {
         self.0.iter().find(|c| c.type_ == *type_)
     }
@@ -232,29 +587,31 @@ impl<C: ConditionType> Conditions<C> {
         // This may be due to the "accessor" interface that we have skipped here.
         match self.get_cond_mut(&condition.type_) {
             Some(cond) => {
-                // Check if only the time has changed
-                let test_cond = Condition {
-                    last_transition_time: condition.last_transition_time,
-                    // OPTIMIZE: could check strings explicitly with no need for clone
-                    ..cond.clone()
-                };
-                if test_cond == condition {
+                // Conditions are equal if only the time has changed
+                if *cond == condition {
                     return
                 } else {
                     *cond = Condition {
-                        last_transition_time: Some(chrono::Utc::now()),
+                        last_transition_time: Some(VolatileTime::now()),
                         ..condition
                     }
                 }
             }
             None => {
-                condition.last_transition_time = Some(chrono::Utc::now());
+                condition.last_transition_time = Some(VolatileTime::now());
                 self.0.push(condition);
-                // TODO: sort the output...alphabetically by type name?
+                self.sort();
             }
         }
     }
 
+    /// Sort alphabetically by the serialized name of the [`ConditionType`], as the go
+    /// implementation does, so that the serialized conditions do not depend on the order they
+    /// were set in.
+    fn sort(&mut self) {
+        self.0.sort_by_cached_key(|c| c.type_.serialized_name());
+    }
+
     fn mark_true(&mut self, condition_type: C) {
         self.set_cond(Condition::with_status(condition_type, ConditionStatus::True))
     }
@@ -275,6 +632,15 @@ impl<C: ConditionType> Conditions<C> {
         });
     }
 
+    fn mark_false_permanent(&mut self, condition_type: C, reason: String, message: Option<String>) {
+        self.set_cond(Condition {
+            reason: Some(reason),
+            message,
+            permanent: true,
+            ..Condition::with_status(condition_type, ConditionStatus::False)
+        });
+    }
+
     fn mark_unknown(&mut self, condition_type: C, reason: String, message: Option<String>) {
         self.set_cond(Condition {
             reason: Some(reason),
@@ -300,75 +666,155 @@ impl<'a, C: ConditionType> ConditionManager<'a, C> {
     }
 
     pub fn get_condition(&self, condition_type: C) -> Option<&Condition<C>> {
-        self.conditions.get_cond(&condition_type)
+        self.conditions.get(&condition_type)
     }
 
-    /// Returns the happy [`Condition`].
-    ///
-    /// # Panic
-    /// Panics if the [`Conditions`] have not been properly initialized.
-    /// See [`Conditions::default()`].
+    /// See [`Conditions::get_top_level_condition`].
     pub fn get_top_level_condition(&self) -> &Condition<C> {
-        self.get_condition(C::happy())
-            .as_ref()
-            .expect("top level condition is initialized")
+        self.conditions.get_top_level_condition()
     }
 
     pub fn is_happy(&self) -> bool {
-        self.get_top_level_condition().is_true()
+        self.conditions.is_happy()
+    }
+
+    /// Returns a clone of all [`Conditions`], sorted by the serialized name of their
+    /// [`ConditionType`].
+    pub fn snapshot(&self) -> Vec<Condition<C>> {
+        let mut conditions = self.conditions.0.clone();
+        conditions.sort_by_cached_key(|c| c.type_.serialized_name());
+        conditions
     }
 
     fn find_unhappy_dependent(&self) -> Option<&Condition<C>> {
         self.conditions.0
             .iter()
-            // Filter to non-true, terminal dependents
-            .filter(|cond| cond.type_ != C::happy() && cond.type_.is_terminal() && !cond.is_true())
-            // Return a condition, prioritizing most recent False over most recent Unknown
-            .reduce(|unhappy, cond| if cond > unhappy { cond } else { unhappy })
+            // Filter to non-true, terminal dependents that are not warnings
+            .filter(|cond| {
+                cond.type_ != C::happy()
+                    && cond.type_.is_terminal()
+                    && cond.severity != ConditionSeverity::Warning
+                    && !cond.is_true()
+            })
+            .reduce(more_unhappy)
     }
 
-    /// Mark the happy condition to true if all other dependents are also true.
-    fn recompute_happiness(&mut self, condition_type: &C) {
-        match self.find_unhappy_dependent() {
-            Some(dependent) => {
-                let cond = Condition {
-                    type_: C::happy(),
-                    status: dependent.status,
-                    reason: dependent.reason.clone(),
-                    message: dependent.message.clone(),
-                    severity: C::happy().severity(),
-                    ..Default::default()
-                };
-                self.conditions.set_cond(cond);
+    /// Recompute each dependent that rolls up `condition_type`, bottom-up, returning the condition
+    /// that the happy condition should then be recomputed from.
+    fn roll_up(&mut self, condition_type: C) -> C {
+        let mut visited = EnumSet::only(condition_type);
+        let mut child = condition_type;
+        while let Some(parent) = EnumSet::<C>::all()
+            .iter()
+            .find(|p| *p != C::happy() && !visited.contains(*p) && p.dependents_of().contains(child))
+        {
+            self.recompute_dependent(parent);
+            visited |= parent;
+            child = parent;
+        }
+        child
+    }
+
+    /// Set a dependent that rolls up sub-conditions from the most unhappy of them, or to true if
+    /// they are all true.
+    fn recompute_dependent(&mut self, parent: C) {
+        let sub_conditions = parent.dependents_of();
+        let unhappy = self.conditions.0
+            .iter()
+            .filter(|cond| {
+                sub_conditions.contains(cond.type_)
+                    && cond.severity != ConditionSeverity::Warning
+                    && !cond.is_true()
+            })
+            .reduce(more_unhappy);
+        let cond = match unhappy {
+            Some(sub_condition) => Condition {
+                status: sub_condition.status,
+                reason: sub_condition.reason.clone(),
+                message: sub_condition.message.clone(),
+                ..Condition::new(parent)
             },
-            None => if *condition_type != C::happy() {
-                // set happy to true
-                self.conditions.set_cond(Condition {
-                    type_: C::happy(),
-                    status: ConditionStatus::True,
-                    severity: C::happy().severity(),
-                    ..Default::default()
-                })
+            None if sub_conditions.iter().any(|c| self.conditions.get_cond(&c).is_none()) => {
+                Condition::new(parent)
             }
+            None => Condition::with_status(parent, ConditionStatus::True),
+        };
+        self.conditions.set_cond(cond);
+    }
+
+    /// The happy condition as determined by the dependents. It takes on the status, reason and
+    /// message of the most unhappy dependent, is unknown while a dependent has not been
+    /// initialized, and is true otherwise.
+    fn happy_condition(&self) -> Condition<C> {
+        match self.find_unhappy_dependent() {
+            Some(dependent) => Condition {
+                status: dependent.status,
+                reason: dependent.reason.clone(),
+                message: dependent.message.clone(),
+                ..Condition::new(C::happy())
+            },
+            // dependents that have not been initialized are not yet known to be happy
+            None if C::dependents().iter()
+                .any(|d| d.blocks_happiness() && self.conditions.get_cond(&d).is_none()) => {
+                Condition::with_status(C::happy(), ConditionStatus::Unknown)
+            },
+            None => Condition::with_status(C::happy(), ConditionStatus::True),
         }
     }
 
+    /// Set the happy condition from the dependents, transitioning it at most once.
+    fn recompute_happiness(&mut self) {
+        let happy = self.happy_condition();
+        self.conditions.set_cond(happy);
+    }
+
+    /// Set the status of the condition type to true.
+    ///
+    /// Marking a condition with its current status, reason and message is a no-op that preserves
+    /// its `last_transition_time`.
+    ///
+    /// The happy condition only becomes true when every dependent is happy; otherwise it takes on
+    /// the status of the most unhappy dependent.
     pub fn mark_true(&mut self, condition_type: C) {
-        self.conditions.mark_true(condition_type);
-        self.recompute_happiness(&condition_type);
+        if condition_type != C::happy() {
+            self.conditions.mark_true(condition_type);
+            self.roll_up(condition_type);
+        }
+        self.recompute_happiness();
     }
 
     pub fn mark_true_with_reason(&mut self, condition_type: C, reason: &str, message: Option<String>) {
-        self.conditions.mark_true_with_reason(condition_type, reason.to_string(), message);
-        self.recompute_happiness(&condition_type);
+        if condition_type != C::happy() {
+            self.conditions.mark_true_with_reason(condition_type, reason.to_string(), message);
+            self.roll_up(condition_type);
+            self.recompute_happiness();
+            return
+        }
+        let happy = match self.happy_condition() {
+            happy if happy.is_true() => Condition { reason: Some(reason.to_string()), message, ..happy },
+            happy => happy,
+        };
+        self.conditions.set_cond(happy);
     }
 
     /// Set the status of the condition type to false, as well as the happy condition if this
-    /// condition is a dependent.
+    /// condition is a dependent. A dependent with a warning severity does not block happiness.
     pub fn mark_false(&mut self, condition_type: C, reason: &str, message: Option<String>) {
         self.conditions.mark_false(condition_type, reason.to_string(), message.clone());
 
-        if C::dependents().contains(condition_type) {
+        if self.roll_up(condition_type).blocks_happiness() {
+            self.conditions.mark_false(C::happy(), reason.to_string(), message)
+        }
+    }
+
+    /// Set the status of the condition type to false as a permanent failure, as well as the happy
+    /// condition if this condition is a dependent.
+    ///
+    /// See [`Conditions::has_permanent_failure`].
+    pub fn mark_false_permanent(&mut self, condition_type: C, reason: &str, message: Option<String>) {
+        self.conditions.mark_false_permanent(condition_type, reason.to_string(), message.clone());
+
+        if self.roll_up(condition_type).blocks_happiness() {
             self.conditions.mark_false(C::happy(), reason.to_string(), message)
         }
     }
@@ -377,17 +823,122 @@ impl<'a, C: ConditionType> ConditionManager<'a, C> {
     /// condition is in an error state.
     pub fn mark_unknown(&mut self, condition_type: C, reason: &str, message: Option<String>) {
         self.conditions.mark_unknown(condition_type, reason.to_string(), message.clone());
+        let dependent = self.roll_up(condition_type);
 
         // set happy condition to false if another dependent is false, otherwise set happy
         // condition to unknown if this condition is a dependent
-        if let Some(dependent) = self.find_unhappy_dependent() {
-            if dependent.is_false() && self.is_happy() {
-                self.mark_false(C::happy(), reason, message);
+        let happy_is_false = self.get_top_level_condition().is_false();
+        match self.find_unhappy_dependent() {
+            // false dependents trump unknown ones, so the happy condition stays false
+            Some(dependent) if dependent.is_false() && happy_is_false => {}
+            Some(dependent) if dependent.is_false() => {
+                let reason = dependent.reason.clone().unwrap_or_default();
+                let message = dependent.message.clone();
+                self.conditions.mark_false(C::happy(), reason, message);
             }
-        } else if condition_type.is_terminal() {
-           self.conditions.mark_unknown(C::happy(), reason.to_string(), message);
+            _ if dependent.blocks_happiness() => {
+                self.conditions.mark_unknown(C::happy(), reason.to_string(), message);
+            }
+            _ => {}
+        }
+    }
+
+    /// Insert the happy condition and any dependents that are missing as unknown, leaving existing
+    /// conditions untouched. This seeds dependents added to a [`ConditionType`] after a resource
+    /// was last reconciled, as with `InitializeConditions` in the go implementation.
+    pub fn initialize_conditions(&mut self) {
+        for condition_type in [C::happy()].into_iter().chain(C::dependents()) {
+            if self.conditions.get_cond(&condition_type).is_none() {
+                self.conditions.set_cond(Condition::new(condition_type));
+            }
+        }
+    }
+
+    /// Remove a condition that does not determine happiness, such as an informational condition
+    /// whose cause has been resolved. Terminal conditions are never removed.
+    pub fn clear_condition(&mut self, condition_type: C) {
+        if condition_type.is_terminal() || self.conditions.get_cond(&condition_type).is_none() {
+            return
+        }
+        self.conditions.0.retain(|c| c.type_ != condition_type);
+        self.recompute_happiness();
+    }
+
+    /// Set the happy condition and all dependents to unknown, typically at the start of
+    /// reconciling a new generation.
+    pub fn mark_all_unknown(&mut self, reason: &str) {
+        for condition_type in [C::happy()].into_iter().chain(C::dependents()) {
+            self.conditions.mark_unknown(condition_type, reason.to_string(), None);
+        }
+    }
+
+    /// Apply several marks, recomputing the happy condition once they have all been applied.
+    ///
+    /// Unlike a sequence of marks, the happy condition does not pass through intermediate states,
+    /// so its `last_transition_time` changes at most once.
+    pub fn batch<F: FnOnce(&mut ConditionBatch<C>)>(&mut self, f: F) {
+        let mut batch = ConditionBatch { conditions: self.conditions, marked: EnumSet::empty() };
+        f(&mut batch);
+        let marked = batch.marked;
+        for condition_type in marked {
+            self.roll_up(condition_type);
+        }
+        if !marked.is_empty() {
+            self.recompute_happiness();
+        }
+    }
+}
+
+/// Prioritize False over Unknown, then the most severe, then the most recent.
+fn more_unhappy<'c, C: ConditionType>(
+    unhappy: &'c Condition<C>,
+    cond: &'c Condition<C>,
+) -> &'c Condition<C> {
+    let severity = match cond.status == unhappy.status {
+        true => cond.severity.cmp(&unhappy.severity),
+        false => std::cmp::Ordering::Equal,
+    };
+    match severity {
+        std::cmp::Ordering::Greater => cond,
+        std::cmp::Ordering::Less => unhappy,
+        std::cmp::Ordering::Equal if cond > unhappy => cond,
+        std::cmp::Ordering::Equal => unhappy,
+    }
+}
+
+/// Marks applied within [`ConditionManager::batch`].
+pub struct ConditionBatch<'a, C: ConditionType> {
+    conditions: &'a mut Conditions<C>,
+    /// The conditions other than the happy condition that were marked.
+    marked: EnumSet<C>,
+}
+
+impl<'a, C: ConditionType> ConditionBatch<'a, C> {
+    fn mark(&mut self, condition_type: C) {
+        if condition_type != C::happy() {
+            self.marked |= condition_type;
         }
     }
+
+    pub fn set_true(&mut self, condition_type: C) {
+        self.conditions.mark_true(condition_type);
+        self.mark(condition_type);
+    }
+
+    pub fn set_true_with_reason(&mut self, condition_type: C, reason: &str, message: Option<String>) {
+        self.conditions.mark_true_with_reason(condition_type, reason.to_string(), message);
+        self.mark(condition_type);
+    }
+
+    pub fn set_false(&mut self, condition_type: C, reason: &str, message: Option<String>) {
+        self.conditions.mark_false(condition_type, reason.to_string(), message);
+        self.mark(condition_type);
+    }
+
+    pub fn set_unknown(&mut self, condition_type: C, reason: &str, message: Option<String>) {
+        self.conditions.mark_unknown(condition_type, reason.to_string(), message);
+        self.mark(condition_type);
+    }
 }
 
 #[cfg(test)]
@@ -395,7 +946,7 @@ mod test {
     use super::*;
     use chrono::TimeZone;
 
-    #[derive(Deserialize, EnumSetType, Debug)]
+    #[derive(Deserialize, Serialize, EnumSetType, Debug, JsonSchema)]
     enum TestCondition {
         Ready,
         SinkProvided,
@@ -426,25 +977,25 @@ mod test {
             Condition {
                 type_: TestCondition::Ready,
                 status: ConditionStatus::False,
-                last_transition_time: Some(dt.and_hms(0, 0, 0)),
+                last_transition_time: Some(dt.and_hms(0, 0, 0).into()),
                 ..Default::default()
             },
             Condition {
                 type_: TestCondition::SinkProvided,
                 status: ConditionStatus::False,
-                last_transition_time: Some(dt.and_hms(3, 0, 0)),
+                last_transition_time: Some(dt.and_hms(3, 0, 0).into()),
                 ..Default::default()
             },
             Condition {
                 type_: TestCondition::OtherCondition,
                 status: ConditionStatus::False,
-                last_transition_time: Some(dt.and_hms(2, 0, 0)),
+                last_transition_time: Some(dt.and_hms(2, 0, 0).into()),
                 ..Default::default()
             },
             Condition {
                 type_: TestCondition::Unimportant,
                 status: ConditionStatus::False,
-                last_transition_time: Some(dt.and_hms(2, 0, 0)),
+                last_transition_time: Some(dt.and_hms(2, 0, 0).into()),
                 ..Default::default()
             },
         ]);
@@ -454,7 +1005,7 @@ mod test {
         // Returns most recent False dependent
         assert_eq!(unhappy.type_, TestCondition::SinkProvided);
         assert_eq!(unhappy.status, ConditionStatus::False);
-        assert_eq!(unhappy.last_transition_time.unwrap(), dt.and_hms(3, 0, 0));
+        assert_eq!(unhappy.last_transition_time.unwrap().0, dt.and_hms(3, 0, 0));
         // Maintains order
         let mut iter = conditions.0.iter();
         assert_eq!(iter.next().unwrap().type_, TestCondition::Ready);
@@ -472,6 +1023,110 @@ mod test {
         ]);
     }
 
+    #[test]
+    fn from_partial_inserts_happy_condition() {
+        let mut conditions = Conditions::from_partial(vec![
+            Condition::with_status(TestCondition::SinkProvided, ConditionStatus::True),
+        ]);
+        let manager = ConditionManager::new(&mut conditions);
+        assert!(manager.get_top_level_condition().is_unknown());
+        assert!(manager.get_condition(TestCondition::SinkProvided).unwrap().is_true());
+        assert!(manager.get_condition(TestCondition::OtherCondition).is_none());
+    }
+
+    #[test]
+    fn from_partial_with_only_happy_condition() {
+        let mut conditions = Conditions::from_partial(vec![
+            Condition::with_status(TestCondition::Ready, ConditionStatus::True),
+        ]);
+        assert_eq!(conditions.0.len(), 1);
+        let mut manager = ConditionManager::new(&mut conditions);
+        assert!(manager.is_happy());
+
+        // dependents may be evaluated later
+        manager.mark_false(TestCondition::SinkProvided, "NoSink", None);
+        assert!(!manager.is_happy());
+    }
+
+    #[test]
+    fn marking_current_state_is_a_no_op() {
+        let dt = chrono::Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_true_with_reason(TestCondition::SinkProvided, "Found", Some("sink found".into()));
+        for c in conditions.0.iter_mut() {
+            c.last_transition_time = Some(dt.into());
+        }
+
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_true_with_reason(TestCondition::SinkProvided, "Found", Some("sink found".into()));
+        assert!(manager.is_happy());
+        for c in conditions.0.iter() {
+            assert_eq!(c.last_transition_time.map(|t| t.0), Some(dt), "{:?} transitioned", c.type_);
+        }
+
+        // a changed message is a transition
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true_with_reason(TestCondition::SinkProvided, "Found", Some("new sink".into()));
+        let sink = manager.get_condition(TestCondition::SinkProvided).unwrap();
+        assert_ne!(sink.last_transition_time.map(|t| t.0), Some(dt));
+        assert_eq!(manager.get_top_level_condition().last_transition_time.map(|t| t.0), Some(dt));
+    }
+
+    #[test]
+    fn mark_all_unknown_resets_terminal_conditions() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_true(TestCondition::Unimportant);
+        assert!(manager.is_happy());
+
+        manager.mark_all_unknown("NewGeneration");
+        for condition_type in [TestCondition::Ready, TestCondition::SinkProvided, TestCondition::OtherCondition] {
+            let condition = manager.get_condition(condition_type).unwrap();
+            assert!(condition.is_unknown());
+            assert_eq!(condition.reason.as_deref(), Some("NewGeneration"));
+        }
+        // non-terminal conditions are untouched
+        assert!(manager.get_condition(TestCondition::Unimportant).unwrap().is_true());
+    }
+
+    #[test]
+    fn merge_from_carries_forward_unset_conditions() {
+        let dt = chrono::Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let previous = Conditions::with_conditions(vec![
+            Condition {
+                type_: TestCondition::Ready,
+                status: ConditionStatus::False,
+                last_transition_time: Some(dt.into()),
+                ..Default::default()
+            },
+            Condition {
+                type_: TestCondition::SinkProvided,
+                status: ConditionStatus::True,
+                last_transition_time: Some(dt.into()),
+                ..Default::default()
+            },
+        ]);
+
+        let mut conditions = Conditions::from_partial(vec![]);
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::OtherCondition);
+        conditions.merge_from(&previous);
+
+        let manager = ConditionManager::new(&mut conditions);
+        let sink = manager.get_condition(TestCondition::SinkProvided).unwrap();
+        assert!(sink.is_true());
+        assert_eq!(sink.last_transition_time.map(|t| t.0), Some(dt));
+        // conditions set this round are not overwritten
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_unknown());
+        assert_ne!(ready.last_transition_time.map(|t| t.0), Some(dt));
+    }
+
     #[test]
     fn condition_type_deserializes() {
         let condition_type: TestCondition = serde_json::from_value(serde_json::json!(
@@ -487,4 +1142,589 @@ mod test {
         ));
         assert!(condition_type.is_err());
     }
+
+    #[test]
+    fn snapshot_shows_cascade_to_happy_condition() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_false(TestCondition::SinkProvided, "NoSink", Some("sink not found".into()));
+
+        let snapshot = manager.snapshot();
+        let types = snapshot.iter().map(|c| c.type_).collect::<Vec<_>>();
+        assert_eq!(types, vec![
+            TestCondition::OtherCondition,
+            TestCondition::Ready,
+            TestCondition::SinkProvided,
+        ]);
+        let ready = &snapshot[1];
+        assert!(ready.is_false());
+        assert_eq!(ready.reason.as_deref(), Some("NoSink"));
+        assert_eq!(ready.message.as_deref(), Some("sink not found"));
+    }
+
+    #[test]
+    fn condition_converts_to_and_from_metav1() {
+        let dt = chrono::Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let condition = Condition {
+            type_: TestCondition::SinkProvided,
+            status: ConditionStatus::False,
+            last_transition_time: Some(dt.into()),
+            message: Some("sink not found".into()),
+            ..Default::default()
+        };
+
+        let metav1 = condition.to_metav1();
+        assert_eq!(metav1.type_, "SinkProvided");
+        assert_eq!(metav1.status, "False");
+        // reason defaults to the type name
+        assert_eq!(metav1.reason, "SinkProvided");
+        assert_eq!(metav1.message, "sink not found");
+        assert_eq!(metav1.last_transition_time, dt);
+        assert_eq!(serde_json::to_value(&metav1).unwrap(), serde_json::json!({
+            "type": "SinkProvided",
+            "status": "False",
+            "lastTransitionTime": "2022-01-01T00:00:00Z",
+            "reason": "SinkProvided",
+            "message": "sink not found"
+        }));
+
+        let round_trip = Condition::<TestCondition>::from_metav1(&metav1).unwrap();
+        assert_eq!(round_trip, Condition {
+            reason: Some("SinkProvided".into()),
+            ..condition
+        });
+
+        let unknown_type = Metav1Condition { type_: "Succeeded".into(), ..metav1 };
+        assert!(Condition::<TestCondition>::from_metav1(&unknown_type).is_none());
+    }
+
+    #[test]
+    fn condition_severity_orders_by_importance() {
+        use ConditionSeverity::*;
+        assert!(Error > Warning);
+        assert!(Warning > Info);
+
+        let mut severities = vec![Warning, Error, Info];
+        severities.sort();
+        assert_eq!(severities, vec![Info, Warning, Error]);
+        assert_eq!(severities.iter().max(), Some(&Error));
+
+        // serde representation is unchanged
+        assert_eq!(serde_json::to_string(&Warning).unwrap(), "\"Warning\"");
+    }
+
+    #[test]
+    fn last_transition_time_schemas_as_date_time() {
+        let schema = serde_json::to_value(schemars::schema_for!(Condition<TestCondition>)).unwrap();
+        let time = &schema["properties"]["last_transition_time"];
+        assert_eq!(time["type"], serde_json::json!(["string", "null"]));
+        assert_eq!(time["format"], "date-time");
+    }
+
+    #[test]
+    fn happy_is_true_only_once_every_dependent_is_true() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+
+        manager.mark_true(TestCondition::SinkProvided);
+        assert!(!manager.is_happy());
+        manager.mark_true(TestCondition::Unimportant);
+        assert!(!manager.is_happy());
+        manager.mark_true_with_reason(TestCondition::OtherCondition, "Done", None);
+        assert!(manager.is_happy());
+
+        // dependents that were never initialized are not assumed to be true
+        let mut conditions = Conditions::<TestCondition>::from_partial(vec![]);
+        let mut manager = ConditionManager::new(&mut conditions);
+
+        manager.mark_true(TestCondition::SinkProvided);
+        assert!(manager.get_top_level_condition().is_unknown());
+        manager.mark_true(TestCondition::OtherCondition);
+        assert!(manager.is_happy());
+    }
+
+    #[test]
+    fn conditions_collect_from_iterator() {
+        let conditions: Conditions<TestCondition> = [TestCondition::SinkProvided, TestCondition::Unimportant]
+            .into_iter()
+            .map(|c| Condition::with_status(c, ConditionStatus::True))
+            .collect();
+        let types = conditions.clone().into_iter().map(|c| c.type_).collect::<Vec<_>>();
+        assert_eq!(types, vec![TestCondition::Ready, TestCondition::SinkProvided, TestCondition::Unimportant]);
+
+        // round trip
+        let round_trip: Conditions<TestCondition> = conditions.clone().into_iter().collect();
+        assert_eq!(round_trip, conditions);
+
+        // filter out conditions that do not determine happiness
+        let terminal: Conditions<TestCondition> = conditions
+            .into_iter()
+            .filter(|c| c.type_.is_terminal())
+            .collect();
+        assert_eq!(terminal.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn mark_false_propagates_reason_and_message_to_happy() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_false(TestCondition::SinkProvided, "Reason", Some("msg".into()));
+
+        for condition_type in [TestCondition::SinkProvided, TestCondition::Ready] {
+            let condition = manager.get_condition(condition_type).unwrap();
+            assert!(condition.is_false());
+            assert_eq!(condition.reason.as_deref(), Some("Reason"));
+            assert_eq!(condition.message.as_deref(), Some("msg"));
+        }
+
+        // a non-dependent does not cascade
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_false(TestCondition::Unimportant, "Other", Some("other msg".into()));
+        assert!(manager.is_happy());
+        assert_eq!(manager.get_top_level_condition().message, None);
+    }
+
+    #[test]
+    fn canonical_json_is_independent_of_order() {
+        let dt = chrono::Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let ready = Condition {
+            type_: TestCondition::Ready,
+            status: ConditionStatus::True,
+            last_transition_time: Some(dt.into()),
+            ..Default::default()
+        };
+        let sink = Condition {
+            type_: TestCondition::SinkProvided,
+            status: ConditionStatus::True,
+            reason: Some("SinkFound".into()),
+            last_transition_time: Some(dt.into()),
+            ..Default::default()
+        };
+
+        let a = Conditions::with_conditions(vec![ready.clone(), sink.clone()]);
+        let b = Conditions::with_conditions(vec![sink, ready]);
+        assert_ne!(a, b);
+        let canonical = serde_json::to_value(a.canonical()).unwrap();
+        assert_eq!(canonical, serde_json::to_value(b.canonical()).unwrap());
+
+        assert_eq!(canonical, serde_json::json!([
+            { "type": "Ready", "status": "True", "last_transition_time": "2022-01-01T00:00:00Z" },
+            {
+                "type": "SinkProvided",
+                "status": "True",
+                "last_transition_time": "2022-01-01T00:00:00Z",
+                "reason": "SinkFound",
+            },
+        ]));
+    }
+
+    #[test]
+    fn mark_unknown_keeps_happy_false_while_a_dependent_is_false() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_false(TestCondition::SinkProvided, "NoSink", Some("sink not found".into()));
+        manager.mark_unknown(TestCondition::OtherCondition, "Waiting", None);
+
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_false());
+        assert_eq!(ready.reason.as_deref(), Some("NoSink"));
+        assert_eq!(ready.message.as_deref(), Some("sink not found"));
+
+        // a happy condition that is not yet false takes the reason of the false dependent
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+        assert!(manager.is_happy());
+        manager.conditions.mark_false(TestCondition::SinkProvided, "NoSink".into(), None);
+        manager.mark_unknown(TestCondition::OtherCondition, "Waiting", None);
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_false());
+        assert_eq!(ready.reason.as_deref(), Some("NoSink"));
+    }
+
+    #[test]
+    fn mark_unknown_dependent_makes_happy_unknown() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+        assert!(manager.is_happy());
+
+        manager.mark_unknown(TestCondition::OtherCondition, "Waiting", None);
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_unknown());
+        assert_eq!(ready.reason.as_deref(), Some("Waiting"));
+
+        // non-dependents do not affect happiness
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_unknown(TestCondition::Unimportant, "Whatever", None);
+        assert!(manager.is_happy());
+    }
+
+    #[test]
+    fn find_unhappy_dependent_prefers_severity_over_recency() {
+        let dt = chrono::Utc.ymd(2022, 1, 1);
+        let mut conditions = Conditions::with_conditions(vec![
+            Condition::new(TestCondition::Ready),
+            Condition {
+                type_: TestCondition::SinkProvided,
+                status: ConditionStatus::False,
+                severity: ConditionSeverity::Error,
+                reason: Some("NoSink".into()),
+                last_transition_time: Some(dt.and_hms(0, 0, 0).into()),
+                ..Default::default()
+            },
+            Condition {
+                type_: TestCondition::OtherCondition,
+                status: ConditionStatus::False,
+                severity: ConditionSeverity::Info,
+                reason: Some("Degraded".into()),
+                last_transition_time: Some(dt.and_hms(1, 0, 0).into()),
+                ..Default::default()
+            },
+        ]);
+        let manager = ConditionManager::new(&mut conditions);
+        let unhappy = manager.find_unhappy_dependent().unwrap();
+        assert_eq!(unhappy.type_, TestCondition::SinkProvided);
+        assert_eq!(unhappy.reason.as_deref(), Some("NoSink"));
+
+        // the more recent condition is only preferred at the same severity
+        conditions.0[1].severity = ConditionSeverity::Info;
+        let manager = ConditionManager::new(&mut conditions);
+        let unhappy = manager.find_unhappy_dependent().unwrap();
+        assert_eq!(unhappy.type_, TestCondition::OtherCondition);
+    }
+
+    #[test]
+    fn happy_reason_is_cleared_when_ready() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_false(TestCondition::SinkProvided, "NoSink", Some("sink not found".into()));
+        assert_eq!(manager.get_top_level_condition().reason.as_deref(), Some("NoSink"));
+
+        manager.mark_true(TestCondition::SinkProvided);
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_true());
+        assert_eq!(ready.reason, None);
+        assert_eq!(ready.message, None);
+    }
+
+    #[test]
+    fn condition_status_displays_as_serialized() {
+        let schema = serde_json::to_value(schemars::schema_for!(ConditionStatus)).unwrap();
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["enum"], serde_json::json!(["True", "False", "Unknown"]));
+
+        for status in [ConditionStatus::True, ConditionStatus::False, ConditionStatus::Unknown] {
+            assert_eq!(serde_json::to_value(status).unwrap(), status.to_string());
+        }
+    }
+
+    #[test]
+    fn batch_transitions_happy_condition_at_most_once() {
+        let dt = chrono::Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+        for c in conditions.0.iter_mut() {
+            c.last_transition_time = Some(dt.into());
+        }
+
+        // a dependent that flaps within the batch does not transition the happy condition
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.batch(|tx| {
+            tx.set_false(TestCondition::SinkProvided, "NoSink", None);
+            tx.set_true(TestCondition::SinkProvided);
+        });
+        assert!(manager.is_happy());
+        assert_eq!(manager.get_top_level_condition().last_transition_time.map(|t| t.0), Some(dt));
+
+        manager.batch(|tx| {
+            tx.set_unknown(TestCondition::OtherCondition, "Checking", None);
+            tx.set_false(TestCondition::SinkProvided, "NoSink", Some("sink not found".into()));
+        });
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_false());
+        assert_eq!(ready.reason.as_deref(), Some("NoSink"));
+        assert_ne!(ready.last_transition_time.map(|t| t.0), Some(dt));
+    }
+
+    #[test]
+    fn permanent_failure_is_cleared_by_marking_true() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_false(TestCondition::SinkProvided, "NoSink", None);
+        assert!(!conditions.has_permanent_failure());
+
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_false_permanent(TestCondition::SinkProvided, "KindNotFound", None);
+        assert!(!manager.is_happy());
+        assert!(conditions.has_permanent_failure());
+
+        let json = serde_json::to_value(&conditions).unwrap();
+        assert_eq!(json.as_array().unwrap().iter().filter(|c| c["permanent"] == true).count(), 1);
+        let round_trip: Conditions<TestCondition> = serde_json::from_value(json).unwrap();
+        assert!(round_trip.has_permanent_failure());
+
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::SinkProvided);
+        assert!(manager.is_happy());
+        assert!(!conditions.has_permanent_failure());
+
+        // conditions that are not permanent failures round-trip unchanged
+        let json = r#"{"type":"SinkProvided","status":"True","last_transition_time":"2022-01-01T00:00:00Z","reason":null,"message":null}"#;
+        let condition: Condition<TestCondition> = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&condition).unwrap(), json);
+    }
+
+    #[test]
+    fn partial_conditions_deserialize_with_happy_condition() {
+        use serde_json::json;
+        let mut conditions: Conditions<TestCondition> = serde_json::from_value(json!([
+            { "type": "SinkProvided", "status": "True" },
+        ])).unwrap();
+        let manager = ConditionManager::new(&mut conditions);
+        assert!(manager.get_top_level_condition().is_unknown());
+
+        let duplicate = serde_json::from_value::<Conditions<TestCondition>>(json!([
+            { "type": "SinkProvided", "status": "True" },
+            { "type": "SinkProvided", "status": "False" },
+        ]));
+        assert!(duplicate.is_err());
+    }
+
+    #[test]
+    fn conditions_are_readable_without_a_manager() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+
+        let conditions = &conditions;
+        assert!(conditions.is_happy());
+        assert!(conditions.get_top_level_condition().is_true());
+        assert!(conditions.get(&TestCondition::SinkProvided).unwrap().is_true());
+        assert!(conditions.get(&TestCondition::Unimportant).is_none());
+    }
+
+    #[test]
+    fn conditions_serialize_in_alphabetical_order() {
+        let types = |conditions: &Conditions<TestCondition>| {
+            serde_json::to_value(conditions).unwrap()
+                .as_array().unwrap()
+                .iter()
+                .map(|c| c["type"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let mut forward = Conditions::from_partial(vec![]);
+        let mut manager = ConditionManager::new(&mut forward);
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_true(TestCondition::Unimportant);
+
+        let mut backward = Conditions::from_partial(vec![]);
+        let mut manager = ConditionManager::new(&mut backward);
+        manager.mark_true(TestCondition::Unimportant);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_true(TestCondition::SinkProvided);
+
+        let expected = ["OtherCondition", "Ready", "SinkProvided", "Unimportant"];
+        assert_eq!(types(&forward), expected);
+        assert_eq!(types(&backward), expected);
+
+        // conditions that were never set are in the same order
+        assert_eq!(types(&Conditions::default()), ["OtherCondition", "Ready", "SinkProvided"]);
+        let partial = Conditions::from_partial(vec![
+            Condition::new(TestCondition::Unimportant),
+            Condition::new(TestCondition::SinkProvided),
+        ]);
+        assert_eq!(types(&partial), ["Ready", "SinkProvided", "Unimportant"]);
+    }
+
+    #[test]
+    fn conditions_differing_only_in_transition_time_are_equal() {
+        let dt = chrono::Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let earlier = Condition {
+            type_: TestCondition::SinkProvided,
+            status: ConditionStatus::True,
+            last_transition_time: Some(dt.into()),
+            ..Default::default()
+        };
+        let later = Condition {
+            last_transition_time: Some(VolatileTime::now()),
+            ..earlier.clone()
+        };
+        assert_eq!(earlier, later);
+        assert_ne!(earlier, Condition { status: ConditionStatus::False, ..later });
+
+        let json = serde_json::to_value(&earlier).unwrap();
+        assert_eq!(json["last_transition_time"], "2022-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn transition_time_deserializes_from_rfc3339_or_epoch() {
+        use serde_json::json;
+        let dt = chrono::Utc.ymd(2022, 1, 1).and_hms(0, 0, 0);
+        let time = |value: serde_json::Value| {
+            let condition: Condition<TestCondition> = serde_json::from_value(json!({
+                "type": "Ready",
+                "status": "True",
+                "last_transition_time": value,
+            })).unwrap();
+            condition.last_transition_time.map(|t| t.0)
+        };
+
+        assert_eq!(time(json!("2022-01-01T00:00:00Z")), Some(dt));
+        assert_eq!(time(json!("2022-01-01T01:00:00+01:00")), Some(dt));
+        assert_eq!(time(json!(dt.timestamp())), Some(dt));
+        assert_eq!(time(json!("")), None);
+        assert_eq!(time(json!(null)), None);
+
+        // a missing time is none
+        let condition: Condition<TestCondition> = serde_json::from_value(json!({
+            "type": "Ready",
+            "status": "True",
+        })).unwrap();
+        assert!(condition.last_transition_time.is_none());
+
+        // epoch seconds round trip as RFC 3339
+        let condition: Condition<TestCondition> = serde_json::from_value(json!({
+            "type": "Ready",
+            "status": "True",
+            "last_transition_time": dt.timestamp(),
+        })).unwrap();
+        let json = serde_json::to_value(&condition).unwrap();
+        assert_eq!(json["last_transition_time"], "2022-01-01T00:00:00Z");
+        assert_eq!(time(json["last_transition_time"].clone()), Some(dt));
+
+        let invalid = serde_json::from_value::<Condition<TestCondition>>(json!({
+            "type": "Ready",
+            "status": "True",
+            "last_transition_time": "yesterday",
+        }));
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn clear_condition_removes_only_non_terminal_conditions() {
+        let mut conditions = Conditions::<TestCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(TestCondition::SinkProvided);
+        manager.mark_true(TestCondition::OtherCondition);
+        manager.mark_true_with_reason(TestCondition::Unimportant, "Deprecated", None);
+        assert!(manager.get_condition(TestCondition::Unimportant).is_some());
+
+        manager.clear_condition(TestCondition::Unimportant);
+        assert!(manager.get_condition(TestCondition::Unimportant).is_none());
+        assert!(manager.is_happy());
+
+        // dependents are kept to compute readiness
+        manager.clear_condition(TestCondition::SinkProvided);
+        manager.clear_condition(TestCondition::Ready);
+        assert!(manager.get_condition(TestCondition::SinkProvided).is_some());
+        assert!(manager.is_happy());
+    }
+
+    #[test]
+    fn initialize_conditions_seeds_missing_dependents() {
+        let mut conditions: Conditions<TestCondition> = serde_json::from_value(serde_json::json!([
+            { "type": "Ready", "status": "False", "reason": "NoSink" },
+        ])).unwrap();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.initialize_conditions();
+
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_false());
+        assert_eq!(ready.reason.as_deref(), Some("NoSink"));
+        for dependent in TestCondition::dependents() {
+            assert!(manager.get_condition(dependent).unwrap().is_unknown(), "{:?}", dependent);
+        }
+        assert!(manager.get_condition(TestCondition::Unimportant).is_none());
+    }
+
+    #[derive(Deserialize, Serialize, EnumSetType, Debug, Default, JsonSchema)]
+    enum ChainCondition {
+        #[default]
+        Ready,
+        IngressReady,
+        CertificateReady,
+    }
+
+    impl ConditionType for ChainCondition {
+        fn happy() -> Self {
+            ChainCondition::Ready
+        }
+
+        fn dependents() -> EnumSet<Self> {
+            EnumSet::only(ChainCondition::IngressReady)
+        }
+
+        fn dependents_of(&self) -> EnumSet<Self> {
+            match self {
+                ChainCondition::Ready => Self::dependents(),
+                ChainCondition::IngressReady => EnumSet::only(ChainCondition::CertificateReady),
+                ChainCondition::CertificateReady => EnumSet::empty(),
+            }
+        }
+    }
+
+    #[test]
+    fn sub_conditions_roll_up_to_happy_condition() {
+        use ChainCondition::*;
+        assert!(CertificateReady.is_terminal());
+
+        let mut conditions = Conditions::<ChainCondition>::default();
+        let mut manager = ConditionManager::new(&mut conditions);
+        manager.mark_true(CertificateReady);
+        assert!(manager.get_condition(IngressReady).unwrap().is_true());
+        assert!(manager.is_happy());
+
+        manager.mark_false(CertificateReady, "CertificateExpired", Some("renew the certificate".into()));
+        for condition_type in [IngressReady, Ready] {
+            let condition = manager.get_condition(condition_type).unwrap();
+            assert!(condition.is_false(), "{:?}", condition_type);
+            assert_eq!(condition.reason.as_deref(), Some("CertificateExpired"));
+            assert_eq!(condition.message.as_deref(), Some("renew the certificate"));
+        }
+
+        manager.mark_unknown(CertificateReady, "Renewing", None);
+        assert!(manager.get_condition(IngressReady).unwrap().is_unknown());
+        assert!(manager.get_top_level_condition().is_unknown());
+
+        manager.mark_true(CertificateReady);
+        assert!(manager.get_condition(IngressReady).unwrap().is_true());
+        assert!(manager.is_happy());
+    }
+
+    #[test]
+    fn stale_returns_conditions_older_than_duration() {
+        let now = chrono::Utc::now();
+        let conditions = Conditions::with_conditions(vec![
+            Condition {
+                type_: TestCondition::Ready,
+                last_transition_time: Some((now - chrono::Duration::hours(2)).into()),
+                ..Default::default()
+            },
+            Condition {
+                type_: TestCondition::SinkProvided,
+                last_transition_time: Some((now - chrono::Duration::minutes(5)).into()),
+                ..Default::default()
+            },
+            Condition {
+                type_: TestCondition::OtherCondition,
+                last_transition_time: None,
+                ..Default::default()
+            },
+        ]);
+
+        let stale = conditions.stale(chrono::Duration::hours(1));
+        assert_eq!(stale.iter().map(|c| c.type_).collect::<Vec<_>>(), [TestCondition::Ready]);
+        assert_eq!(conditions.stale(chrono::Duration::minutes(1)).len(), 2);
+        assert!(conditions.stale(chrono::Duration::days(1)).is_empty());
+    }
 }
@@ -4,16 +4,18 @@ use syn::Error;
 use std::fmt;
 
 pub enum VerificationError {
-    NotDependent(String),
-    OneRequiredVariant,
+    NotDependent(String, Span),
+    NoSeverity(String, Span),
+    OneRequiredVariant(Span),
 }
 
 impl fmt::Display for VerificationError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use VerificationError::*;
         match self {
-            NotDependent(s) => f.write_fmt(format_args!("{} may not be a dependent", s)),
-            OneRequiredVariant => f.write_fmt(
+            NotDependent(s, _) => f.write_fmt(format_args!("{} may not be a dependent", s)),
+            NoSeverity(s, _) => f.write_fmt(format_args!("{} may not have a severity", s)),
+            OneRequiredVariant(_) => f.write_fmt(
                 format_args!(
                     "ConditionType must contain only one of either {} variant",
                     REQUIRED_VARIANTS.join(" or ")
@@ -23,8 +25,19 @@ impl fmt::Display for VerificationError {
     }
 }
 
+impl VerificationError {
+    fn span(&self) -> Span {
+        use VerificationError::*;
+        match self {
+            NotDependent(_, span)
+            | NoSeverity(_, span)
+            | OneRequiredVariant(span) => *span,
+        }
+    }
+}
+
 impl From<VerificationError> for Error {
     fn from(v: VerificationError) -> Error {
-        Error::new(Span::call_site(), v)
+        Error::new(v.span(), v)
     }
 }
@@ -3,6 +3,8 @@ use crate::{
     error::VerificationError
 };
 use proc_macro::TokenStream;
+use proc_macro2::Span;
+use std::collections::HashSet;
 use quote::quote;
 use syn::{
     spanned::Spanned,
@@ -16,11 +18,107 @@ use syn::{
     Ident
 };
 
-fn is_dependent(variant: &syn::Variant) -> bool {
+fn has_attr(variant: &syn::Variant, attr: &str) -> bool {
     variant.attrs
         .iter()
         .any(|a| a.path.segments.iter()
-             .any(|p| p.ident == "dependent"))
+             .any(|p| p.ident == attr))
+}
+
+fn is_dependent(variant: &syn::Variant) -> bool {
+    has_attr(variant, "dependent")
+}
+
+const SEVERITIES: [&str; 3] = ["Error", "Warning", "Info"];
+
+/// The `ConditionSeverity` variant of a condition, from `#[severity = "..."]`. `#[warning]` is
+/// shorthand for `#[severity = "Warning"]`, and may not be combined with it.
+fn severity(variant: &syn::Variant) -> Result<Option<Ident>> {
+    let warning = variant.attrs.iter().find(|a| a.path.is_ident("warning"));
+    let attr = match (variant.attrs.iter().find(|a| a.path.is_ident("severity")), warning) {
+        (Some(_), Some(warning)) => return Err(Error::new(
+            warning.path.span(),
+            "`#[warning]` is shorthand for `#[severity = \"Warning\"]`, use only one of them"
+        )),
+        (None, Some(_)) => return Ok(Some(Ident::new("Warning", variant.ident.span()))),
+        (Some(attr), None) => attr,
+        (None, None) => return Ok(None),
+    };
+    match attr.parse_meta()? {
+        syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(lit), .. }) => {
+            match SEVERITIES.contains(&lit.value().as_str()) {
+                true => Ok(Some(Ident::new(&lit.value(), lit.span()))),
+                false => Err(Error::new(
+                    lit.span(),
+                    format!("unknown severity `{}`, expected one of {}", lit.value(), SEVERITIES.join(", "))
+                )),
+            }
+        }
+        _ => Err(Error::new(attr.span(), "expected `severity = \"...\"`")),
+    }
+}
+
+/// The value of `#[serde(<key> = "...")]` among `attrs`, or of `#[serde(<key>(serialize = "..."))]`.
+fn serde_attr(attrs: &[syn::Attribute], key: &str) -> Option<String> {
+    use syn::{Lit, Meta, NestedMeta};
+
+    let metas = attrs.iter()
+        .filter(|a| a.path.is_ident("serde"))
+        .filter_map(|a| match a.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten();
+    for meta in metas {
+        match meta {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident(key) => {
+                if let Lit::Str(lit) = nv.lit {
+                    return Some(lit.value())
+                }
+            }
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident(key) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("serialize") => {
+                            if let Lit::Str(lit) = nv.lit {
+                                return Some(lit.value())
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The name of a variant as serde serializes it, following `#[serde(rename)]` on the variant or
+/// `#[serde(rename_all)]` on the enum.
+fn serialized_name(ast: &DeriveInput, variant: &Variant) -> Result<String> {
+    if let Some(rename) = serde_attr(&variant.attrs, "rename") {
+        return Ok(rename)
+    }
+    let name = variant.ident.to_string();
+    let snake_case = || name.chars()
+        .enumerate()
+        .flat_map(|(i, c)| match i > 0 && c.is_uppercase() {
+            true => vec!['_', c.to_ascii_lowercase()],
+            false => vec![c.to_ascii_lowercase()],
+        })
+        .collect::<String>();
+    match serde_attr(&ast.attrs, "rename_all").as_deref() {
+        None | Some("PascalCase") => Ok(name),
+        Some("lowercase") => Ok(name.to_ascii_lowercase()),
+        Some("UPPERCASE") => Ok(name.to_ascii_uppercase()),
+        Some("camelCase") => Ok(name[..1].to_ascii_lowercase() + &name[1..]),
+        Some("snake_case") => Ok(snake_case()),
+        Some("SCREAMING_SNAKE_CASE") => Ok(snake_case().to_ascii_uppercase()),
+        Some("kebab-case") => Ok(snake_case().replace('_', "-")),
+        Some("SCREAMING-KEBAB-CASE") => Ok(snake_case().to_ascii_uppercase().replace('_', "-")),
+        Some(rule) => Err(Error::new(ast.ident.span(), format!("unknown serde rename rule `{rule}`"))),
+    }
 }
 
 fn verify_variants(variants: &Punctuated<Variant, Comma>) -> Result<()> {
@@ -31,19 +129,23 @@ fn verify_variants(variants: &Punctuated<Variant, Comma>) -> Result<()> {
         if REQUIRED_VARIANTS.contains(&name.as_str()) {
             // Ensure top level conditions are not dependents
             if is_dependent(&v) {
-                Err(VerificationError::NotDependent(name))?
+                Err(VerificationError::NotDependent(name, v.ident.span()))?
+            }
+            // Ensure top level conditions always determine readiness
+            if severity(v)?.is_some() {
+                Err(VerificationError::NoSeverity(v.ident.to_string(), v.ident.span()))?
             }
             // Ensure only one top level condition exists
             if !one_required {
                 one_required = true;
             } else {
-                Err(VerificationError::OneRequiredVariant)?
+                Err(VerificationError::OneRequiredVariant(v.ident.span()))?
             }
         }
     }
 
     if !one_required {
-        Err(VerificationError::OneRequiredVariant)?
+        Err(VerificationError::OneRequiredVariant(Span::call_site()))?
     }
 
     Ok(())
@@ -60,15 +162,55 @@ pub fn inner_derive(ast: DeriveInput) -> Result<TokenStream> {
         ))
     };
 
+    // rustc already rejects duplicate variants (E0428), and code generated for them would only
+    // add errors of its own, so generate nothing and leave that as the only error
+    let mut seen = HashSet::new();
+    if !variants.iter().all(|v| seen.insert(&v.ident)) {
+        return Ok(TokenStream::new())
+    }
+
     verify_variants(variants)?;
 
-    let happy = &variants.iter()
+    let happy_variant = variants.iter()
         .find(|v| REQUIRED_VARIANTS.contains(&v.ident.to_string().as_str()))
-        .unwrap()
-        .ident;
+        .unwrap();
+    let happy = &happy_variant.ident;
+    let happy_name = serialized_name(&ast, happy_variant)?;
+    let happy_name_doc = format!("The name of the happy condition of [`{name}`], as it is serialized.");
     let dependents = variants.iter()
         .filter(|v| is_dependent(v))
         .map(|v| &v.ident);
+    let dependent_count = dependents.clone().count();
+    let mut severe = Vec::new();
+    let mut severities = Vec::new();
+    for v in variants {
+        if let Some(severity) = severity(v)? {
+            severe.push(&v.ident);
+            severities.push(severity);
+        }
+    }
+    let severity = match severe.is_empty() {
+        true => quote! {},
+        false => quote! {
+            #[inline]
+            fn severity(&self) -> ::knative_conditions::ConditionSeverity {
+                match self {
+                    #(#name::#severe => ::knative_conditions::ConditionSeverity::#severities,)*
+                    _ if ::knative_conditions::ConditionType::is_terminal(self) => {
+                        ::knative_conditions::ConditionSeverity::Error
+                    }
+                    _ => ::knative_conditions::ConditionSeverity::Info,
+                }
+            }
+        },
+    };
+    let all = variants.iter().map(|v| &v.ident);
+    let named = all.clone();
+    let serialized_names = variants.iter()
+        .map(|v| serialized_name(&ast, v))
+        .collect::<Result<Vec<_>>>()?;
+    let all_doc = format!("Every variant of [`{name}`], in declaration order.");
+    let dependent_count_doc = format!("The number of dependents of [`{name}`], as returned by `dependents()`.");
 
     let capitalized = variants.iter()
         .map(|v| v.ident.clone())
@@ -83,6 +225,8 @@ pub fn inner_derive(ast: DeriveInput) -> Result<TokenStream> {
     let mark = lower_case.clone().map(|l| Ident::new(&format!("mark_{l}"), l.span()));
     let mark_with_reason = lower_case.clone().map(|l| Ident::new(&format!("mark_{l}_with_reason"), l.span()));
     let mark_not = lower_case.clone().map(|l| Ident::new(&format!("mark_not_{l}"), l.span()));
+    let mark_unknown = lower_case.clone().map(|l| Ident::new(&format!("mark_{l}_unknown"), l.span()));
+    let is = lower_case.clone().map(|l| Ident::new(&format!("is_{l}"), l.span()));
 
     let condition_type_name = Ident::new(&format!("{name}Type"), name.span());
     let condition_type_doc = format!("A [`ConditionType`] that implement this trait duck types to [`{name}`].");
@@ -119,6 +263,29 @@ pub fn inner_derive(ast: DeriveInput) -> Result<TokenStream> {
             fn dependents() -> ::enumset::EnumSet<Self> {
                 ::enumset::enum_set!(#(#name::#dependents)|*)
             }
+
+            #[inline]
+            fn serialized_name(&self) -> String {
+                String::from(match self {
+                    #(#name::#named => #serialized_names,)*
+                })
+            }
+
+            #severity
+        }
+
+        #[automatically_derived]
+        impl #name {
+            #[doc = #happy_name_doc]
+            pub const HAPPY_NAME: &'static str = #happy_name;
+
+            #[doc = #dependent_count_doc]
+            pub const DEPENDENT_COUNT: usize = #dependent_count;
+
+            #[doc = #all_doc]
+            pub fn all() -> &'static [Self] {
+                &[#(#name::#all),*]
+            }
         }
 
         #[automatically_derived]
@@ -143,6 +310,16 @@ pub fn inner_derive(ast: DeriveInput) -> Result<TokenStream> {
                 fn #mark_not(&mut self, reason: &str, message: Option<String>) {
                     self.manager().mark_false(S::#lower_case_again_again(), reason, message);
                 }
+
+                fn #mark_unknown(&mut self, reason: &str, message: Option<String>) {
+                    self.manager().mark_unknown(S::#lower_case_again_again(), reason, message);
+                }
+
+                fn #is(&mut self) -> bool {
+                    self.manager()
+                        .get_condition(S::#lower_case_again_again())
+                        .map_or(false, |c| c.is_true())
+                }
             )*
         }
 
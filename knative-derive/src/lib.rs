@@ -29,12 +29,21 @@ const REQUIRED_VARIANTS: [&str; 2] = ["Ready", "Succeeded"];
 ///   SinkProvided,
 ///   #[dependent]
 ///   Important,
+///   // Set the severity of a condition explicitly. Conditions
+///   // with a warning severity never block resource readiness
+///   #[dependent]
+///   #[severity = "Warning"]
+///   Throttled,
+///   // The warning attribute is shorthand for a warning severity
+///   #[dependent]
+///   #[warning]
+///   Degraded,
 ///   // Conditions that are not marked dependent do not
 ///   // determine overall resource readiness
 ///   Informational,
 /// }
 /// ```
-#[proc_macro_derive(ConditionType, attributes(dependent))]
+#[proc_macro_derive(ConditionType, attributes(dependent, warning, severity))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = parse_macro_input!(input);
 
@@ -1,6 +1,6 @@
 use knative_derive::ConditionType;
 use knative_conditions::ConditionType as _;
-use knative_conditions::{ConditionAccessor, Conditions};
+use knative_conditions::{Condition, ConditionAccessor, ConditionSeverity, ConditionStatus, Conditions};
 use enumset::EnumSetType;
 
 #[derive(ConditionType, EnumSetType, Debug)]
@@ -31,8 +31,213 @@ fn has_dependents() {
     assert_eq!(MyCondition::SinkProvided, MyCondition::dependents());
 }
 
+#[derive(ConditionType, EnumSetType, Debug)]
+enum ManyDependents {
+    Succeeded,
+    #[dependent]
+    First,
+    Informational,
+    #[dependent]
+    Second,
+    #[dependent]
+    Third,
+}
+
+#[test]
+fn lists_all_variants() {
+    assert_eq!(MyCondition::all(), &[MyCondition::Ready, MyCondition::SinkProvided]);
+    assert_eq!(ManyDependents::all().len(), 5);
+    assert_eq!(ManyDependents::all()[3], ManyDependents::Second);
+}
+
+#[test]
+fn counts_dependents() {
+    assert_eq!(MyCondition::DEPENDENT_COUNT, 1);
+    assert_eq!(ManyDependents::DEPENDENT_COUNT, 3);
+    assert_eq!(ManyDependents::DEPENDENT_COUNT, ManyDependents::dependents().len());
+}
+
+#[test]
+fn names_happy_condition() {
+    assert_eq!(MyCondition::HAPPY_NAME, "Ready");
+    assert_eq!(ManyDependents::HAPPY_NAME, "Succeeded");
+}
+
+#[derive(ConditionType, EnumSetType, Debug, serde::Serialize)]
+enum RenamedCondition {
+    #[serde(rename = "Available")]
+    Ready,
+}
+
+#[derive(ConditionType, EnumSetType, Debug, serde::Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum ScreamingCondition {
+    Succeeded,
+    #[dependent]
+    SinkProvided,
+}
+
+#[test]
+fn happy_name_follows_serde_rename() {
+    assert_eq!(RenamedCondition::HAPPY_NAME, "Available");
+    assert_eq!(serde_json::to_value(RenamedCondition::Ready).unwrap(), RenamedCondition::HAPPY_NAME);
+    assert_eq!(ScreamingCondition::HAPPY_NAME, "SUCCEEDED");
+    assert_eq!(serde_json::to_value(ScreamingCondition::Succeeded).unwrap(), ScreamingCondition::HAPPY_NAME);
+}
+
+#[test]
+fn serialized_name_follows_serde_rename() {
+    assert_eq!(MyCondition::SinkProvided.serialized_name(), "SinkProvided");
+    assert_eq!(RenamedCondition::Ready.serialized_name(), "Available");
+    assert_eq!(ScreamingCondition::SinkProvided.serialized_name(), "SINK_PROVIDED");
+    assert_eq!(
+        serde_json::to_value(ScreamingCondition::SinkProvided).unwrap(),
+        ScreamingCondition::SinkProvided.serialized_name()
+    );
+}
+
+#[test]
+fn renamed_condition_round_trips_through_metav1() {
+    let condition = Condition::with_status(ScreamingCondition::SinkProvided, ConditionStatus::False);
+    let metav1 = condition.to_metav1();
+    assert_eq!(metav1.type_, "SINK_PROVIDED");
+    assert_eq!(metav1.status, "False");
+    assert_eq!(Condition::<ScreamingCondition>::from_metav1(&metav1).unwrap().type_, ScreamingCondition::SinkProvided);
+}
+
 #[test]
 fn can_be_managed() {
     let mut status = MyStatus { conditions: Conditions::default() };
     status.mark_sinkprovided();
 }
+
+#[test]
+fn can_check_condition_status() {
+    let mut status = MyStatus { conditions: Conditions::default() };
+    assert!(!status.is_sinkprovided());
+
+    status.mark_sinkprovided();
+    assert!(status.is_sinkprovided());
+
+    status.mark_not_sinkprovided("NoSink", None);
+    assert!(!status.is_sinkprovided());
+}
+
+#[test]
+fn mark_ready_defers_to_unhappy_dependents() {
+    let mut status = MyStatus { conditions: Conditions::default() };
+    status.mark_not_sinkprovided("NoSink", None);
+
+    status.mark_ready();
+    assert!(!status.is_ready());
+    let ready = status.manager().get_top_level_condition().clone();
+    assert!(ready.is_false());
+    assert_eq!(ready.reason.as_deref(), Some("NoSink"));
+
+    status.mark_sinkprovided();
+    status.mark_ready();
+    assert!(status.is_ready());
+}
+
+#[test]
+fn mark_ready_does_not_transition_an_unready_condition() {
+    let mut status = MyStatus { conditions: Conditions::default() };
+    status.mark_not_sinkprovided("NoSink", None);
+    let transitioned = status.manager().get_top_level_condition().last_transition_time.map(|t| t.0);
+
+    status.mark_ready();
+    let ready = status.manager().get_top_level_condition().clone();
+    assert!(ready.is_false());
+    assert_eq!(ready.last_transition_time.map(|t| t.0), transitioned);
+}
+
+#[test]
+fn mark_ready_is_unknown_until_dependents_are_initialized() {
+    let mut status = MyStatus { conditions: Conditions::from_partial(vec![]) };
+    status.mark_ready();
+    assert!(status.manager().get_top_level_condition().is_unknown());
+
+    status.mark_sinkprovided();
+    assert!(status.is_ready());
+}
+
+#[test]
+fn can_mark_unknown() {
+    let mut status = MyStatus { conditions: Conditions::default() };
+    status.mark_sinkprovided();
+    assert!(status.is_ready());
+
+    status.mark_sinkprovided_unknown("Waiting", Some("waiting on the sink".into()));
+    let sink = status.manager().get_condition(MyCondition::SinkProvided).cloned().unwrap();
+    assert!(sink.is_unknown());
+    assert_eq!(sink.reason.as_deref(), Some("Waiting"));
+    assert!(status.manager().get_top_level_condition().is_unknown());
+}
+
+#[derive(ConditionType, EnumSetType, Debug, serde::Serialize)]
+enum WarningCondition {
+    Ready,
+    #[dependent]
+    SinkProvided,
+    #[dependent]
+    #[warning]
+    Degraded,
+    #[dependent]
+    #[severity = "Warning"]
+    Throttled,
+    Informational,
+}
+
+struct WarningStatus {
+    conditions: Conditions<WarningCondition>
+}
+
+impl ConditionAccessor<WarningCondition> for WarningStatus {
+    fn conditions(&mut self) -> &mut Conditions<WarningCondition> {
+        &mut self.conditions
+    }
+}
+
+#[test]
+fn warnings_do_not_block_readiness() {
+    assert_eq!(WarningCondition::Degraded.severity(), ConditionSeverity::Warning);
+    assert_eq!(WarningCondition::Throttled.severity(), ConditionSeverity::Warning);
+    assert_eq!(WarningCondition::SinkProvided.severity(), ConditionSeverity::Error);
+    assert_eq!(WarningCondition::Informational.severity(), ConditionSeverity::Info);
+
+    let mut status = WarningStatus { conditions: Conditions::default() };
+    status.mark_sinkprovided();
+    assert!(status.is_ready());
+
+    status.mark_not_degraded("Slow", Some("sink is responding slowly".into()));
+    assert!(status.is_ready());
+    let degraded = status.manager().get_condition(WarningCondition::Degraded).cloned().unwrap();
+    assert!(degraded.is_false());
+    assert_eq!(degraded.severity, ConditionSeverity::Warning);
+
+    // error severities are omitted when serialized
+    let json = serde_json::to_value(&status.conditions).unwrap();
+    for condition in json.as_array().unwrap() {
+        match condition["type"].as_str().unwrap() {
+            "Degraded" | "Throttled" => assert_eq!(condition["severity"], "Warning"),
+            _ => assert!(condition.get("severity").is_none(), "{condition}"),
+        }
+    }
+
+    status.mark_not_throttled("RateLimited", None);
+    assert!(status.is_ready());
+
+    status.mark_not_sinkprovided("NoSink", None);
+    assert!(!status.is_ready());
+}
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/severity-valid.rs");
+    t.compile_fail("tests/ui/severity-invalid.rs");
+    t.compile_fail("tests/ui/warning-and-severity.rs");
+    t.compile_fail("tests/ui/two-required-variants.rs");
+    t.compile_fail("tests/ui/dependent-happy-variant.rs");
+    t.compile_fail("tests/ui/duplicate-variant.rs");
+}
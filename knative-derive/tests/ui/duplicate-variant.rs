@@ -0,0 +1,12 @@
+use knative_derive::ConditionType;
+
+#[derive(ConditionType)]
+enum MyCondition {
+    Ready,
+    #[dependent]
+    SinkProvided,
+    #[dependent]
+    SinkProvided,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use knative_derive::ConditionType;
+use knative_conditions::{ConditionSeverity, ConditionType as _};
+use enumset::EnumSetType;
+
+#[derive(ConditionType, EnumSetType, Debug)]
+enum MyCondition {
+    Ready,
+    #[dependent]
+    #[severity = "Warning"]
+    Degraded,
+    #[severity = "Error"]
+    Informational,
+}
+
+fn main() {
+    assert_eq!(MyCondition::Degraded.severity(), ConditionSeverity::Warning);
+    assert_eq!(MyCondition::Informational.severity(), ConditionSeverity::Error);
+}
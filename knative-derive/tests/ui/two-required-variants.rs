@@ -0,0 +1,12 @@
+use knative_derive::ConditionType;
+use enumset::EnumSetType;
+
+#[derive(ConditionType, EnumSetType, Debug)]
+enum MyCondition {
+    Ready,
+    #[dependent]
+    SinkProvided,
+    Succeeded,
+}
+
+fn main() {}
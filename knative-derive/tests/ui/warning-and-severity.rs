@@ -0,0 +1,13 @@
+use knative_derive::ConditionType;
+use enumset::EnumSetType;
+
+#[derive(ConditionType, EnumSetType, Debug)]
+enum MyCondition {
+    Ready,
+    #[dependent]
+    #[warning]
+    #[severity = "Info"]
+    Degraded,
+}
+
+fn main() {}
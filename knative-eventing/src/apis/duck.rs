@@ -0,0 +1,28 @@
+//! The duck types of [`knative`] that are exposed in the specs and statuses of the resources of
+//! this crate, re-exported so that users have a single import root.
+//!
+//! ```rust
+//! use knative_eventing::apis::duck::{Destination, SourceSpec};
+//!
+//! let spec = SourceSpec {
+//!     sink: Some(Destination::from_uri("http://sink.default.svc".parse().unwrap()).unwrap()),
+//!     ce_overrides: None,
+//! };
+//! assert!(spec.validate().is_ok());
+//! ```
+
+pub use knative::{
+    addressable_type::Addressable,
+    binding_types::{BindingSpec, Reference, Subject},
+    delivery_types::DeliverySpec,
+    knative_reference::KReference,
+    source_types::{
+        CloudEventOverrides,
+        Destination,
+        SinkManager,
+        SourceCondition,
+        SourceSpec,
+        SourceStatus,
+    },
+    status_types::Status,
+};
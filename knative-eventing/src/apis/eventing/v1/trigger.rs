@@ -0,0 +1,165 @@
+use kube::CustomResource;
+use knative::{
+    addressable_type::Addressable,
+    conditions::{ConditionAccessor, Conditions},
+    delivery_types::DeliverySpec,
+    derive::{ConditionType, EnumSetType},
+    source_types::Destination,
+    status_types::{FieldManager, Status},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Trigger represents a request to have events delivered to a subscriber from a Broker's event
+/// pool.
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(
+    kind = "Trigger",
+    group = "eventing.knative.dev",
+    status = "TriggerStatus",
+    version = "v1",
+    namespaced,
+    printcolumn = r#"{"name":"Broker", "type":"string", "jsonPath":".spec.broker"}"#,
+    printcolumn = r#"{"name":"Subscriber_URI", "type":"string", "jsonPath":".status.subscriberUri"}"#,
+    printcolumn = r#"{"name":"Ready", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].reason"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerSpec {
+    /// Broker is the broker that this trigger receives events from.
+    pub broker: String,
+    /// Filter is the filter to apply against all events from the Broker. Only events that pass
+    /// this filter will be sent to the Subscriber. If not specified, will default to allowing all
+    /// events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<TriggerFilter>,
+    /// Subscriber is the addressable that receives events from the Broker that pass the Filter.
+    pub subscriber: Destination,
+    /// Delivery contains the delivery spec for this specific trigger.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<DeliverySpec>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct TriggerFilter {
+    /// Attributes filters events by exact match on event context attributes.
+    /// Each key in the map is compared with the equivalent key in the event
+    /// context. An event passes the filter if all values are equal to the
+    /// specified values.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<BTreeMap<String, String>>,
+}
+
+/// The [`ConditionType`] of [`TriggerStatus`].
+#[derive(ConditionType, EnumSetType, Deserialize, Serialize, Debug, JsonSchema)]
+pub enum TriggerCondition {
+    Ready,
+    /// The broker of the trigger is ready.
+    #[dependent]
+    BrokerReady,
+    /// The subscriber of the trigger has been resolved to an address.
+    #[dependent]
+    SubscriberResolved,
+}
+
+/// Communicates the observed state of the [`Trigger`] (from the controller).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerStatus {
+    /// inherits [`Status`], which currently provides:
+    /// * observed_generation
+    /// * conditions
+    #[serde(flatten)]
+    pub status: Status<TriggerCondition>,
+    /// SubscriberURI is the resolved URI of the receiver for this Trigger.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscriber_uri: Option<url::Url>,
+    /// SubscriberCACerts is the Certification Authority (CA) certificates in PEM format that the
+    /// source trusts when sending events to the subscriber.
+    #[serde(rename = "subscriberCACerts", skip_serializing_if = "Option::is_none")]
+    pub subscriber_ca_certs: Option<String>,
+    /// SubscriberAudience is the OIDC audience of the subscriber.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscriber_audience: Option<String>,
+}
+
+impl ConditionAccessor<TriggerCondition> for TriggerStatus {
+    fn conditions(&mut self) -> &mut Conditions<TriggerCondition> {
+        self.status.conditions()
+    }
+}
+
+impl FieldManager for Trigger {
+    const FIELD_MANAGER: &'static str = "trigger-controller.knative-rs";
+}
+
+impl TriggerStatus {
+    /// Set the resolved address of the subscriber, and the condition that it is resolved.
+    pub fn mark_subscriber_resolved(&mut self, addressable: Addressable) {
+        self.subscriber_uri = addressable.url;
+        self.subscriber_ca_certs = addressable.ca_certs;
+        self.subscriber_audience = addressable.audience;
+        self.mark_subscriberresolved();
+    }
+
+    /// Clear the address of the subscriber, and set the condition that it failed to resolve.
+    pub fn mark_subscriber_not_resolved(&mut self, reason: &str, message: Option<String>) {
+        self.subscriber_uri = None;
+        self.subscriber_ca_certs = None;
+        self.subscriber_audience = None;
+        self.mark_not_subscriberresolved(reason, message);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn subscriber() -> Addressable {
+        Addressable {
+            name: None,
+            url: Some("https://subscriber.default.svc.cluster.local/".parse().unwrap()),
+            ca_certs: Some("-----BEGIN CERTIFICATE-----".into()),
+            audience: Some("subscriber".into()),
+        }
+    }
+
+    #[test]
+    fn marks_subscriber_resolved() {
+        let mut status = TriggerStatus::default();
+        status.mark_subscriber_resolved(subscriber());
+
+        assert_eq!(
+            status.subscriber_uri.as_ref().map(url::Url::as_str),
+            Some("https://subscriber.default.svc.cluster.local/")
+        );
+        assert_eq!(status.subscriber_ca_certs.as_deref(), Some("-----BEGIN CERTIFICATE-----"));
+        assert_eq!(status.subscriber_audience.as_deref(), Some("subscriber"));
+        let resolved = status.manager().get_condition(TriggerCondition::SubscriberResolved).cloned();
+        assert!(resolved.unwrap().is_true());
+        // the broker is not yet ready
+        assert!(!status.is_ready());
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["subscriberCACerts"], "-----BEGIN CERTIFICATE-----");
+        assert_eq!(json["subscriberAudience"], "subscriber");
+    }
+
+    #[test]
+    fn marks_subscriber_not_resolved() {
+        let mut status = TriggerStatus::default();
+        status.mark_subscriber_resolved(subscriber());
+        status.mark_brokerready();
+        assert!(status.is_ready());
+
+        status.mark_subscriber_not_resolved("Unresolvable", Some("subscriber not found".into()));
+        assert!(status.subscriber_uri.is_none());
+        assert!(status.subscriber_ca_certs.is_none());
+        assert!(status.subscriber_audience.is_none());
+        assert!(!status.is_ready());
+        let ready = status.manager().get_top_level_condition().clone();
+        assert_eq!(ready.reason.as_deref(), Some("Unresolvable"));
+    }
+}
@@ -0,0 +1,324 @@
+use kube::CustomResource;
+use knative::{
+    conditions::{ConditionAccessor, Conditions},
+    delivery_types::DeliverySpec,
+    derive::{ConditionType, EnumSetType},
+    error::Error,
+    source_types::Destination,
+    status_types::{FieldManager, Status},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+/// Parallel defines conditional branches that will be wired in series through Channels and
+/// Subscriptions.
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(
+    kind = "Parallel",
+    group = "flows.knative.dev",
+    status = "ParallelStatus",
+    version = "v1",
+    namespaced,
+    printcolumn = r#"{"name":"Ready", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].reason"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ParallelSpec {
+    /// Branches is the list of Filter/Subscribers pairs.
+    pub branches: Vec<ParallelBranch>,
+    /// Reply is a Reference to where the result of a case Subscriber gets sent to when the case
+    /// does not have a Reply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<Destination>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ParallelBranch {
+    /// Filter is the expression guarding the branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Destination>,
+    /// Subscriber receiving the event when the filter passes
+    pub subscriber: Destination,
+    /// Reply is a Reference to where the result of Subscriber of this case gets sent to.
+    /// If not specified, sent the result to the Parallel Reply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<Destination>,
+    /// Delivery is the delivery specification for events to the subscriber
+    /// This includes things like retries, DLQ, etc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<DeliverySpec>,
+}
+
+/// The resolved destinations of a [`ParallelBranch`]. Each destination is resolved independently,
+/// so that one failing destination does not hide the others.
+#[derive(Debug)]
+pub struct BranchResolution {
+    pub filter: Option<Result<url::Url, Error>>,
+    pub subscriber: Result<url::Url, Error>,
+    pub reply: Option<Result<url::Url, Error>>,
+    pub dead_letter_sink: Option<Result<url::Url, Error>>,
+}
+
+impl BranchResolution {
+    /// Whether every destination of the branch resolved.
+    pub fn is_resolved(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// The name and error of each destination that failed to resolve.
+    pub fn errors(&self) -> impl Iterator<Item = (&'static str, &Error)> {
+        [
+            ("filter", self.filter.as_ref()),
+            ("subscriber", Some(&self.subscriber)),
+            ("reply", self.reply.as_ref()),
+            ("deadLetterSink", self.dead_letter_sink.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(name, result)| Some((name, result?.as_ref().err()?)))
+    }
+}
+
+impl Parallel {
+    /// Resolve the destinations of every branch, relative to the namespace of the parallel.
+    pub async fn resolve_branches(&self, client: kube::Client) -> Vec<BranchResolution> {
+        self.resolve_branches_with(|destination| destination.resolve_uri_for(client.clone(), self)).await
+    }
+
+    /// Resolve the destinations of every branch with `resolve`. A branch without a reply replies
+    /// to the reply of the parallel.
+    pub async fn resolve_branches_with<'a, F, Fut>(&'a self, resolve: F) -> Vec<BranchResolution>
+    where
+        F: Fn(&'a Destination) -> Fut,
+        Fut: Future<Output = Result<url::Url, Error>>,
+    {
+        let mut resolutions = Vec::with_capacity(self.spec.branches.len());
+        for branch in &self.spec.branches {
+            let dead_letter_sink = branch.delivery.as_ref().and_then(|d| d.dead_letter_sink.as_ref());
+            resolutions.push(BranchResolution {
+                filter: resolve_optional(branch.filter.as_ref(), &resolve).await,
+                subscriber: resolve(&branch.subscriber).await,
+                reply: resolve_optional(branch.reply.as_ref().or(self.spec.reply.as_ref()), &resolve).await,
+                dead_letter_sink: resolve_optional(dead_letter_sink, &resolve).await,
+            });
+        }
+        resolutions
+    }
+}
+
+async fn resolve_optional<'a, F, Fut>(
+    destination: Option<&'a Destination>,
+    resolve: &F,
+) -> Option<Result<url::Url, Error>>
+where
+    F: Fn(&'a Destination) -> Fut,
+    Fut: Future<Output = Result<url::Url, Error>>,
+{
+    match destination {
+        Some(destination) => Some(resolve(destination).await),
+        None => None,
+    }
+}
+
+/// The [`ConditionType`] of [`ParallelStatus`].
+#[derive(ConditionType, EnumSetType, Deserialize, Serialize, Debug, JsonSchema)]
+pub enum ParallelCondition {
+    Ready,
+    /// The destinations of every branch have been resolved.
+    #[dependent]
+    BranchesResolved,
+}
+
+/// Communicates the observed state of the [`Parallel`] (from the controller).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ParallelStatus {
+    /// inherits [`Status`], which currently provides:
+    /// * observed_generation
+    /// * conditions
+    #[serde(flatten)]
+    pub status: Status<ParallelCondition>,
+    /// BranchStatuses is an array of corresponding to branch statuses.
+    /// Matches the Spec.Branches array in the order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_statuses: Option<Vec<ParallelBranchStatus>>,
+}
+
+/// The resolved destinations of a branch, or why they could not be resolved.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParallelBranchStatus {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter_uri: Option<url::Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscriber_uri: Option<url::Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_uri: Option<url::Url>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_sink_uri: Option<url::Url>,
+    /// A description of the destinations that failed to resolve.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl From<&BranchResolution> for ParallelBranchStatus {
+    fn from(resolution: &BranchResolution) -> Self {
+        let ok = |result: Option<&Result<url::Url, Error>>| result?.as_ref().ok().cloned();
+        let errors = resolution.errors()
+            .map(|(name, err)| format!("{name}: {err}"))
+            .collect::<Vec<_>>();
+        ParallelBranchStatus {
+            filter_uri: ok(resolution.filter.as_ref()),
+            subscriber_uri: ok(Some(&resolution.subscriber)),
+            reply_uri: ok(resolution.reply.as_ref()),
+            dead_letter_sink_uri: ok(resolution.dead_letter_sink.as_ref()),
+            message: (!errors.is_empty()).then(|| errors.join(", ")),
+        }
+    }
+}
+
+impl ConditionAccessor<ParallelCondition> for ParallelStatus {
+    fn conditions(&mut self) -> &mut Conditions<ParallelCondition> {
+        self.status.conditions()
+    }
+}
+
+impl FieldManager for Parallel {
+    const FIELD_MANAGER: &'static str = "parallel-controller.knative-rs";
+}
+
+impl ParallelStatus {
+    /// Set the status of each branch, marking the branches resolved only if every destination of
+    /// every branch resolved.
+    pub fn mark_branches(&mut self, resolutions: &[BranchResolution]) {
+        let unresolved = resolutions.iter()
+            .enumerate()
+            .filter(|(_, r)| !r.is_resolved())
+            .map(|(i, _)| i.to_string())
+            .collect::<Vec<_>>();
+
+        match unresolved.is_empty() {
+            true => self.mark_branchesresolved(),
+            false => self.mark_not_branchesresolved(
+                "BranchesUnresolved",
+                Some(format!("branches {} failed to resolve", unresolved.join(", "))),
+            ),
+        }
+
+        self.branch_statuses = Some(resolutions.iter().map(ParallelBranchStatus::from).collect());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use knative::{error::Error, knative_reference::KReference, source_types::DestinationErr};
+    use std::future::ready;
+
+    fn service(name: &str) -> Destination {
+        Destination::from_ref(KReference {
+            kind: "Service".into(),
+            namespace: Some("default".into()),
+            name: name.into(),
+            api_version: Some("serving.knative.dev/v1".into()),
+            group: None,
+        })
+    }
+
+    fn branch(subscriber: &str) -> ParallelBranch {
+        ParallelBranch {
+            filter: None,
+            subscriber: service(subscriber),
+            reply: None,
+            delivery: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_failing_branch() {
+        let parallel = Parallel::new("parallel", ParallelSpec {
+            branches: vec![
+                ParallelBranch {
+                    filter: Some(service("filter")),
+                    reply: Some(service("reply")),
+                    delivery: Some(DeliverySpec {
+                        dead_letter_sink: Some(service("dls")),
+                        ..Default::default()
+                    }),
+                    ..branch("first")
+                },
+                ParallelBranch {
+                    reply: Some(service("reply")),
+                    ..branch("missing")
+                },
+            ],
+            reply: None,
+        });
+
+        let lookup = |reference: &KReference| ready(match reference.name.as_str() {
+            "missing" => Err(Error::from(DestinationErr::Empty)),
+            name => Ok(format!("http://{name}.default.svc.cluster.local/").parse().unwrap()),
+        });
+        let resolutions = parallel
+            .resolve_branches_with(|destination| destination.resolve_uri_with(lookup))
+            .await;
+
+        assert_eq!(resolutions.len(), 2);
+        assert!(resolutions[0].is_resolved());
+        assert!(!resolutions[1].is_resolved());
+        // the reply of the failing branch is still resolved
+        assert!(matches!(resolutions[1].reply, Some(Ok(_))));
+
+        let mut status = ParallelStatus::default();
+        status.mark_branches(&resolutions);
+        assert!(!status.is_ready());
+        let resolved = status.manager().get_condition(ParallelCondition::BranchesResolved).cloned().unwrap();
+        assert_eq!(resolved.message.as_deref(), Some("branches 1 failed to resolve"));
+
+        let branches = status.branch_statuses.unwrap();
+        assert_eq!(
+            branches[0].dead_letter_sink_uri.as_ref().map(url::Url::as_str),
+            Some("http://dls.default.svc.cluster.local/")
+        );
+        assert!(branches[0].message.is_none());
+        assert!(branches[1].subscriber_uri.is_none());
+        assert!(branches[1].reply_uri.is_some());
+        assert!(branches[1].message.as_deref().unwrap().starts_with("subscriber: "));
+    }
+
+    #[tokio::test]
+    async fn branch_replies_to_parallel_reply() {
+        let parallel = Parallel::new("parallel", ParallelSpec {
+            branches: vec![
+                branch("first"),
+                ParallelBranch {
+                    reply: Some(service("reply")),
+                    ..branch("second")
+                },
+            ],
+            reply: Some(service("parallel-reply")),
+        });
+
+        let lookup = |reference: &KReference| ready(
+            Ok(format!("http://{}.default.svc.cluster.local/", reference.name).parse().unwrap())
+        );
+        let resolutions = parallel
+            .resolve_branches_with(|destination| destination.resolve_uri_with(lookup))
+            .await;
+
+        let mut status = ParallelStatus::default();
+        status.mark_branches(&resolutions);
+        let branches = status.branch_statuses.unwrap();
+        assert_eq!(
+            branches[0].reply_uri.as_ref().map(url::Url::as_str),
+            Some("http://parallel-reply.default.svc.cluster.local/")
+        );
+        // the reply of a branch takes precedence
+        assert_eq!(
+            branches[1].reply_uri.as_ref().map(url::Url::as_str),
+            Some("http://reply.default.svc.cluster.local/")
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use crate::error::Error;
+use kube::CustomResource;
+use knative::{
+    conditions::{ConditionAccessor, Conditions},
+    delivery_types::DeliverySpec,
+    derive::{ConditionType, EnumSetType},
+    knative_reference::KReference,
+    source_types::Destination,
+    status_types::{FieldManager, Status},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Subscription routes events received on a Channel to a DNS name and
+/// corresponds to the subscriptions.channels.knative.dev CRD.
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(
+    kind = "Subscription",
+    group = "messaging.knative.dev",
+    status = "SubscriptionStatus",
+    version = "v1",
+    namespaced,
+    printcolumn = r#"{"name":"Ready", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].reason"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionSpec {
+    /// Reference to a channel that will be used to create the subscription.
+    /// This field is immutable.
+    pub channel: KReference,
+    /// Subscriber is reference to function for processing events.
+    /// Events from the Channel will be delivered here and replies are
+    /// sent to a Destination as specified by the Reply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscriber: Option<Destination>,
+    /// Reply specifies (optionally) how to handle events returned from
+    /// the Subscriber target.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<Destination>,
+    /// Delivery configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delivery: Option<DeliverySpec>,
+}
+
+/// The [`ConditionType`] of [`SubscriptionStatus`].
+#[derive(ConditionType, EnumSetType, Deserialize, Serialize, Debug, JsonSchema)]
+pub enum SubscriptionCondition {
+    Ready,
+    /// The channel, subscriber, reply and dead letter sink of the subscription have been
+    /// resolved.
+    #[dependent]
+    ReferencesResolved,
+    /// The subscription has been added to the subscribers of its channel.
+    #[dependent]
+    AddedToChannel,
+    /// The channel of the subscription is ready.
+    #[dependent]
+    ChannelReady,
+}
+
+/// Communicates the observed state of the [`Subscription`] (from the controller).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionStatus {
+    /// inherits [`Status`], which currently provides:
+    /// * observed_generation
+    /// * conditions
+    #[serde(flatten)]
+    pub status: Status<SubscriptionCondition>,
+    /// PhysicalSubscription is the fully resolved values that this Subscription represents.
+    #[serde(default)]
+    pub physical_subscription: PhysicalSubscription,
+}
+
+/// The resolved addresses of the destinations of a [`Subscription`].
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalSubscription {
+    /// SubscriberURI is the fully resolved URI for spec.subscriber.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscriber_uri: Option<url::Url>,
+    /// SubscriberCACerts is the Certification Authority (CA) certificates in PEM format that the
+    /// channel trusts when sending events to the subscriber.
+    #[serde(rename = "subscriberCACerts", skip_serializing_if = "Option::is_none")]
+    pub subscriber_ca_certs: Option<String>,
+    /// SubscriberAudience is the OIDC audience of the subscriber.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscriber_audience: Option<String>,
+    /// ReplyURI is the fully resolved URI for the spec.reply.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_uri: Option<url::Url>,
+    /// DeadLetterSinkURI is the resolved URI of the dead letter sink of spec.delivery.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_sink_uri: Option<url::Url>,
+}
+
+impl ConditionAccessor<SubscriptionCondition> for SubscriptionStatus {
+    fn conditions(&mut self) -> &mut Conditions<SubscriptionCondition> {
+        self.status.conditions()
+    }
+}
+
+impl FieldManager for Subscription {
+    const FIELD_MANAGER: &'static str = "subscription-controller.knative-rs";
+}
+
+impl SubscriptionStatus {
+    /// Set the resolved addresses of the subscription, and the condition that they are resolved.
+    pub fn mark_references_resolved(&mut self, physical_subscription: PhysicalSubscription) {
+        self.physical_subscription = physical_subscription;
+        self.mark_referencesresolved();
+    }
+
+    /// Clear the resolved addresses of the subscription, and set the condition that they failed
+    /// to resolve.
+    pub fn mark_references_not_resolved(&mut self, reason: &str, message: Option<String>) {
+        self.physical_subscription = PhysicalSubscription::default();
+        self.mark_not_referencesresolved(reason, message);
+    }
+}
+
+impl Subscription {
+    /// Resolve the uri that replies from the subscriber are sent to.
+    ///
+    /// An explicit `reply` is resolved relative to the namespace of the subscription. Without
+    /// one, replies are routed back to the address of the `channel` that the subscription
+    /// consumes from, which is likewise resolved relative to the namespace of the subscription.
+    ///
+    /// Triggers have no equivalent, as they have no `reply` and always send replies back to their
+    /// broker.
+    pub async fn resolve_reply(&self, client: kube::Client) -> Result<url::Url, Error> {
+        match (&self.spec.reply, self.metadata.namespace.as_deref()) {
+            (Some(reply), _) => Ok(reply.resolve_uri_for(client, self).await?),
+            (None, Some(namespace)) => Ok(self.spec.channel.resolve_uri_in(client, namespace).await?),
+            (None, None) => Ok(self.spec.channel.resolve_uri(client).await?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use http::{Request, Response};
+    use hyper::Body;
+    use serde_json::json;
+
+    fn subscription(reply: Option<Destination>) -> Subscription {
+        let mut subscription = Subscription::new("subscription", SubscriptionSpec {
+            channel: KReference {
+                kind: "InMemoryChannel".into(),
+                namespace: None,
+                name: "channel".into(),
+                api_version: Some("messaging.knative.dev/v1".into()),
+                group: None,
+            },
+            subscriber: None,
+            reply,
+            delivery: None,
+        });
+        subscription.metadata.namespace = Some("default".into());
+        subscription
+    }
+
+    /// A client serving the `channel` and `reply` InMemoryChannels of the `default` namespace.
+    fn client() -> kube::Client {
+        let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        tokio::spawn(async move {
+            while let Some((request, send)) = handle.next_request().await {
+                let path = request.uri().path();
+                let name = path.strip_prefix("/apis/messaging.knative.dev/v1/namespaces/default/inmemorychannels/");
+                let body = match (path, name) {
+                    ("/apis/messaging.knative.dev/v1", _) => json!({
+                        "kind": "APIResourceList",
+                        "apiVersion": "v1",
+                        "groupVersion": "messaging.knative.dev/v1",
+                        "resources": [{
+                            "name": "inmemorychannels",
+                            "singularName": "inmemorychannel",
+                            "namespaced": true,
+                            "kind": "InMemoryChannel",
+                            "verbs": ["get", "list", "watch"]
+                        }]
+                    }),
+                    (_, Some(name @ ("channel" | "reply"))) => json!({
+                        "apiVersion": "messaging.knative.dev/v1",
+                        "kind": "InMemoryChannel",
+                        "metadata": { "name": name, "namespace": "default" },
+                        "status": {
+                            "address": { "url": format!("http://{name}-kn-channel.default.svc.cluster.local") }
+                        }
+                    }),
+                    _ => panic!("unexpected request for {path}"),
+                };
+                send.send_response(Response::new(Body::from(serde_json::to_vec(&body).unwrap())));
+            }
+        });
+        kube::Client::new(service, "default")
+    }
+
+    /// A client for resolutions that must not reach the API server.
+    fn unused_client() -> kube::Client {
+        let (service, _handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        kube::Client::new(service, "default")
+    }
+
+    #[tokio::test]
+    async fn resolves_explicit_reply() {
+        let reply = Destination::from_uri("http://reply.default.svc.cluster.local/".parse().unwrap()).unwrap();
+        let uri = subscription(Some(reply))
+            .resolve_reply(unused_client())
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://reply.default.svc.cluster.local/");
+    }
+
+    #[tokio::test]
+    async fn resolves_reply_ref_in_subscription_namespace() {
+        let reply = Destination::from_ref(KReference {
+            kind: "InMemoryChannel".into(),
+            namespace: None,
+            name: "reply".into(),
+            api_version: Some("messaging.knative.dev/v1".into()),
+            group: None,
+        });
+        let uri = subscription(Some(reply))
+            .resolve_reply(client())
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://reply-kn-channel.default.svc.cluster.local/");
+    }
+
+    #[tokio::test]
+    async fn reply_defaults_to_channel() {
+        let uri = subscription(None)
+            .resolve_reply(client())
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://channel-kn-channel.default.svc.cluster.local/");
+    }
+
+    #[test]
+    fn marks_references_resolved() {
+        let mut status = SubscriptionStatus::default();
+        status.mark_references_resolved(PhysicalSubscription {
+            subscriber_uri: Some("http://subscriber.default.svc.cluster.local/".parse().unwrap()),
+            ..Default::default()
+        });
+        assert!(status.physical_subscription.subscriber_uri.is_some());
+        let resolved = status.manager().get_condition(SubscriptionCondition::ReferencesResolved).cloned();
+        assert!(resolved.unwrap().is_true());
+        // the channel is not yet known to be ready
+        assert!(!status.is_ready());
+
+        status.mark_references_not_resolved("SubscriberNotFound", Some("subscriber is missing".into()));
+        assert_eq!(status.physical_subscription, PhysicalSubscription::default());
+        let resolved = status.manager().get_condition(SubscriptionCondition::ReferencesResolved).cloned().unwrap();
+        assert_eq!(resolved.reason.as_deref(), Some("SubscriberNotFound"));
+    }
+}
@@ -1 +1,5 @@
+pub mod duck;
+pub mod eventing;
+pub mod flows;
+pub mod messaging;
 pub mod sources;
@@ -0,0 +1,300 @@
+use crate::error::Error;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use knative::{
+    binding_types::label_selector_query,
+    source_types::{SourceSpec, SourceStatus, SourceCondition},
+};
+use kube::{
+    api::{DynamicObject, GroupVersionKind, ListParams},
+    discovery::{self, Scope},
+    Api, CustomResource,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// ApiServerSource is an event source that brings Kubernetes API server events into Knative.
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(
+    kind = "ApiServerSource",
+    group = "sources.knative.dev",
+    status = "ApiServerSourceStatus",
+    version = "v1",
+    namespaced,
+    printcolumn = r#"{"name":"Sink", "type":"string", "jsonPath":".status.sinkUri"}"#,
+    printcolumn = r#"{"name":"Ready", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].reason"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiServerSourceSpec {
+    /// Sink and CloudEventOverrides
+    #[serde(flatten)]
+    pub source_spec: SourceSpec,
+    /// Resources are the resources this source will track and send related
+    /// lifecycle events from the Kubernetes ApiServer, with an optional label
+    /// selector to help filter.
+    pub resources: Vec<ApiVersionKindSelector>,
+    /// ResourceOwner is an additional filter to only track resources that are
+    /// owned by a specific resource type. If ResourceOwner matches Resources[n]
+    /// then Resources[n] is allowed to pass the ResourceOwner filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<ApiVersionKindSelector>,
+    /// EventMode controls the format of the event.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<EventMode>,
+    /// ServiceAccountName is the name of the ServiceAccount to use to run this
+    /// source. Defaults to default if not set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_account_name: Option<String>,
+}
+
+/// The format of the events sent by an [`ApiServerSource`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default, JsonSchema)]
+pub enum EventMode {
+    /// Send a reference to the changed object as the event data.
+    #[default]
+    #[serde(alias = "Ref")]
+    Reference,
+    /// Send the full changed object as the event data.
+    Resource,
+}
+
+/// Communicates the observed state of the [`ApiServerSource`] (from the controller).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct ApiServerSourceStatus {
+    /// inherits [`SourceStatus`], which currently provides:
+    /// * observed_generation
+    /// * conditions
+    /// * sink_uri
+    #[serde(flatten)]
+    pub source_status: SourceStatus<SourceCondition>,
+}
+
+/// APIVersionKindSelector is an APIVersion Kind tuple with a LabelSelector.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiVersionKindSelector {
+    /// APIVersion - the API version of the resource to watch.
+    pub api_version: String,
+    /// Kind of the resource to watch.
+    /// More info: https://git.k8s.io/community/contributors/devel/sig-architecture/api-conventions.md#types-kinds
+    pub kind: String,
+    /// LabelSelector filters this source to objects to those resources pass the
+    /// label selector.
+    /// More info: http://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<LabelSelector>,
+}
+
+/// The objects matched by an [`ApiVersionKindSelector`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectedResources {
+    pub api_version: String,
+    pub kind: String,
+    /// Names of the matching objects.
+    pub names: Vec<String>,
+}
+
+/// List the objects in `namespace` that each of the `resources` would watch, so that an operator
+/// can preview what an ApiServerSource will emit events for. Cluster scoped resources, such as
+/// namespaces or nodes, are listed across the cluster.
+pub async fn list_selected(
+    client: kube::Client,
+    namespace: &str,
+    resources: &[ApiVersionKindSelector],
+) -> Result<Vec<SelectedResources>, Error> {
+    let mut selected = Vec::with_capacity(resources.len());
+
+    for resource in resources {
+        let (group, version) = resource.api_version
+            .split_once('/')
+            .unwrap_or(("", &resource.api_version));
+        let gvk = GroupVersionKind::gvk(group, version, &resource.kind);
+        let (ar, caps) = discovery::pinned_kind(&client, &gvk).await?;
+        let api = match caps.scope {
+            Scope::Cluster => Api::<DynamicObject>::all_with(client.clone(), &ar),
+            Scope::Namespaced => Api::<DynamicObject>::namespaced_with(client.clone(), namespace, &ar),
+        };
+
+        let mut params = ListParams::default();
+        if let Some(selector) = &resource.selector {
+            params = params.labels(&label_selector_query(selector));
+        }
+
+        selected.push(SelectedResources {
+            api_version: resource.api_version.clone(),
+            kind: resource.kind.clone(),
+            names: api.list(&params).await?
+                .items
+                .into_iter()
+                .filter_map(|obj| obj.metadata.name)
+                .collect(),
+        });
+    }
+
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use knative::{
+        conditions::ConditionAccessor,
+        source_types::SinkManager,
+        status_types::HasStatus,
+    };
+    use http::{Request, Response};
+    use hyper::Body;
+    use serde_json::json;
+
+    fn pod(name: &str, app: &str) -> serde_json::Value {
+        json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "name": name, "namespace": "default", "labels": { "app": app } }
+        })
+    }
+
+    #[test]
+    fn deserializes_api_server_source() {
+        let source: ApiServerSource = serde_yaml::from_str(r#"
+apiVersion: sources.knative.dev/v1
+kind: ApiServerSource
+metadata:
+  name: testevents
+  namespace: default
+spec:
+  mode: Resource
+  resources:
+    - apiVersion: v1
+      kind: Event
+    - apiVersion: apps/v1
+      kind: Deployment
+      selector:
+        matchLabels:
+          app: my-app
+  serviceAccountName: events-sa
+  sink:
+    ref:
+      apiVersion: v1
+      kind: Service
+      name: event-display
+"#).unwrap();
+
+        assert_eq!(source.metadata.name.as_deref(), Some("testevents"));
+        let spec = &source.spec;
+        assert_eq!(spec.mode, Some(EventMode::Resource));
+        assert_eq!(spec.service_account_name.as_deref(), Some("events-sa"));
+        assert_eq!(spec.resources.len(), 2);
+        assert_eq!(spec.resources[0].kind, "Event");
+        assert!(spec.resources[0].selector.is_none());
+        assert_eq!(spec.resources[1].api_version, "apps/v1");
+        let labels = spec.resources[1].selector.as_ref().unwrap().match_labels.as_ref().unwrap();
+        assert_eq!(labels.get("app").map(String::as_str), Some("my-app"));
+        assert!(spec.owner.is_none());
+        assert!(spec.source_spec.validate().is_ok());
+        assert!(source.status.is_none());
+
+        let mode: EventMode = serde_json::from_value(json!("Ref")).unwrap();
+        assert_eq!(mode, EventMode::Reference);
+        assert_eq!(serde_json::to_value(EventMode::default()).unwrap(), json!("Reference"));
+    }
+
+    #[test]
+    fn can_mark_sink_on_api_server_source() {
+        let mut source = ApiServerSource::new("testevents", ApiServerSourceSpec {
+            source_spec: SourceSpec::default(),
+            resources: Vec::new(),
+            owner: None,
+            mode: None,
+            service_account_name: None,
+        });
+        let status = source.status_mut();
+        assert!(!status.is_ready());
+        status.mark_sink("http://event-display.default.svc.cluster.local/".parse().unwrap());
+        assert!(status.is_ready());
+    }
+
+    #[tokio::test]
+    async fn lists_selected_resources() {
+        let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        tokio::spawn(async move {
+            while let Some((request, send)) = handle.next_request().await {
+                let body = match (request.uri().path(), request.uri().query()) {
+                    ("/api/v1", _) => json!({
+                        "kind": "APIResourceList",
+                        "apiVersion": "v1",
+                        "groupVersion": "v1",
+                        "resources": [{
+                            "name": "pods",
+                            "singularName": "pod",
+                            "namespaced": true,
+                            "kind": "Pod",
+                            "verbs": ["get", "list", "watch"]
+                        }, {
+                            "name": "namespaces",
+                            "singularName": "namespace",
+                            "namespaced": false,
+                            "kind": "Namespace",
+                            "verbs": ["get", "list", "watch"]
+                        }]
+                    }),
+                    ("/api/v1/namespaces", _) => {
+                        let namespaces = ["default", "kube-system"].map(|name| json!({
+                            "apiVersion": "v1",
+                            "kind": "Namespace",
+                            "metadata": { "name": name }
+                        }));
+                        json!({
+                            "apiVersion": "v1",
+                            "kind": "NamespaceList",
+                            "metadata": {},
+                            "items": namespaces
+                        })
+                    }
+                    ("/api/v1/namespaces/default/pods", query) => {
+                        let selected = query.unwrap_or_default().contains("labelSelector=app%3Dmy-app");
+                        let pods = [pod("a", "my-app"), pod("b", "my-app"), pod("c", "other")];
+                        json!({
+                            "apiVersion": "v1",
+                            "kind": "PodList",
+                            "metadata": {},
+                            "items": match selected {
+                                true => pods[..2].to_vec(),
+                                false => pods.to_vec(),
+                            }
+                        })
+                    }
+                    (path, _) => panic!("unexpected request for {path}"),
+                };
+                send.send_response(Response::new(Body::from(serde_json::to_vec(&body).unwrap())));
+            }
+        });
+        let client = kube::Client::new(service, "default");
+
+        let resources: Vec<ApiVersionKindSelector> = serde_json::from_value(json!([
+            { "apiVersion": "v1", "kind": "Pod", "selector": { "matchLabels": { "app": "my-app" } } },
+            { "apiVersion": "v1", "kind": "Pod" },
+            { "apiVersion": "v1", "kind": "Namespace" }
+        ])).unwrap();
+        let selected = list_selected(client, "default", &resources).await.unwrap();
+
+        assert_eq!(selected, vec![
+            SelectedResources {
+                api_version: "v1".into(),
+                kind: "Pod".into(),
+                names: vec!["a".into(), "b".into()],
+            },
+            SelectedResources {
+                api_version: "v1".into(),
+                kind: "Pod".into(),
+                names: vec!["a".into(), "b".into(), "c".into()],
+            },
+            SelectedResources {
+                api_version: "v1".into(),
+                kind: "Namespace".into(),
+                names: vec!["default".into(), "kube-system".into()],
+            },
+        ]);
+    }
+}
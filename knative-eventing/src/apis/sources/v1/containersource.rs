@@ -0,0 +1,92 @@
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::CustomResource;
+use knative::{
+    source_types::{SourceSpec, SourceStatus, SourceCondition},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// ContainerSource is the Schema for the ContainerSources API. It runs the containers of its
+/// template and sends their events to the sink.
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(
+    kind = "ContainerSource",
+    group = "sources.knative.dev",
+    status = "ContainerSourceStatus",
+    version = "v1",
+    namespaced,
+    printcolumn = r#"{"name":"Sink", "type":"string", "jsonPath":".status.sinkUri"}"#,
+    printcolumn = r#"{"name":"Ready", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].reason"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerSourceSpec {
+    /// Template describes the pods that will be created.
+    pub template: PodTemplateSpec,
+    /// Sink and CloudEventOverrides
+    #[serde(flatten)]
+    pub source_spec: SourceSpec,
+}
+
+/// Communicates the observed state of the [`ContainerSource`] (from the controller).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct ContainerSourceStatus {
+    /// inherits [`SourceStatus`], which currently provides:
+    /// * observed_generation
+    /// * conditions
+    /// * sink_uri
+    #[serde(flatten)]
+    pub source_status: SourceStatus<SourceCondition>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trips_container_source() {
+        let source: ContainerSource = serde_yaml::from_str(r#"
+apiVersion: sources.knative.dev/v1
+kind: ContainerSource
+metadata:
+  name: test-heartbeats
+spec:
+  template:
+    spec:
+      containers:
+        - image: gcr.io/knative-releases/knative.dev/eventing/cmd/heartbeats:latest
+          name: heartbeats
+          args:
+            - --period=1
+          env:
+            - name: POD_NAME
+              value: "mypod"
+            - name: POD_NAMESPACE
+              value: "event-test"
+  sink:
+    ref:
+      apiVersion: serving.knative.dev/v1
+      kind: Service
+      name: event-display
+"#).unwrap();
+
+        let round_trip: ContainerSource = serde_json::from_value(
+            serde_json::to_value(&source).unwrap()
+        ).unwrap();
+
+        let containers = round_trip.spec.template.spec.as_ref().unwrap().containers.clone();
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].name, "heartbeats");
+        assert_eq!(containers[0].args.as_deref(), Some(&["--period=1".to_string()][..]));
+        assert_eq!(containers[0].env.as_ref().unwrap().len(), 2);
+
+        let sink = serde_json::to_value(&round_trip.spec.source_spec.sink).unwrap();
+        assert_eq!(sink["ref"], serde_json::json!({
+            "apiVersion": "serving.knative.dev/v1",
+            "kind": "Service",
+            "name": "event-display",
+        }));
+        assert!(round_trip.spec.source_spec.validate().is_ok());
+    }
+}
@@ -1 +1,4 @@
+pub mod apiserversource;
+pub mod containersource;
+pub mod pingsource;
 pub mod sinkbinding;
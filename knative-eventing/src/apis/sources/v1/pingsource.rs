@@ -0,0 +1,177 @@
+use kube::CustomResource;
+use knative::{
+    source_types::{SourceSpec, SourceStatus, SourceCondition},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// PingSource is the Schema for the PingSources API. It sends events with a fixed payload on a
+/// cron schedule.
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(
+    kind = "PingSource",
+    group = "sources.knative.dev",
+    status = "PingSourceStatus",
+    version = "v1",
+    namespaced,
+    printcolumn = r#"{"name":"Sink", "type":"string", "jsonPath":".status.sinkUri"}"#,
+    printcolumn = r#"{"name":"Schedule", "type":"string", "jsonPath":".spec.schedule"}"#,
+    printcolumn = r#"{"name":"Ready", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].reason"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
+)]
+#[serde(rename_all = "camelCase")]
+pub struct PingSourceSpec {
+    /// Sink and CloudEventOverrides
+    #[serde(flatten)]
+    pub source_spec: SourceSpec,
+    /// Schedule is the cron schedule. Defaults to `* * * * *`.
+    #[serde(default = "default_schedule")]
+    pub schedule: String,
+    /// Timezone modifies the actual time relative to the specified timezone.
+    /// Defaults to the system time zone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// ContentType is the media type of `data` or `dataBase64`. Default is empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Data is data used as the body of the event posted to the sink. Default is empty.
+    /// Mutually exclusive with `dataBase64`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    /// DataBase64 is the base64-encoded string of the actual event's body posted to the sink.
+    /// Default is empty. Mutually exclusive with `data`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_base64: Option<String>,
+}
+
+fn default_schedule() -> String {
+    "* * * * *".into()
+}
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum PingSourceErr {
+    #[error("ping source missing data and dataBase64, expected exactly one")]
+    NoData,
+    #[error("ping source has both data and dataBase64, expected exactly one")]
+    BothData,
+}
+
+impl PingSourceSpec {
+    /// A spec that sends `data` or `data_base64` to the sink of `source_spec` on `schedule`.
+    /// Exactly one of `data` and `data_base64` must be set.
+    pub fn new(
+        schedule: &str,
+        source_spec: SourceSpec,
+        data: Option<String>,
+        data_base64: Option<String>,
+    ) -> Result<Self, PingSourceErr> {
+        let spec = PingSourceSpec {
+            source_spec,
+            schedule: schedule.into(),
+            timezone: None,
+            content_type: None,
+            data,
+            data_base64,
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Validate that exactly one of `data` and `data_base64` is set.
+    pub fn validate(&self) -> Result<(), PingSourceErr> {
+        match (&self.data, &self.data_base64) {
+            (None, None) => Err(PingSourceErr::NoData),
+            (Some(_), Some(_)) => Err(PingSourceErr::BothData),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Communicates the observed state of the [`PingSource`] (from the controller).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct PingSourceStatus {
+    /// inherits [`SourceStatus`], which currently provides:
+    /// * observed_generation
+    /// * conditions
+    /// * sink_uri
+    #[serde(flatten)]
+    pub source_status: SourceStatus<SourceCondition>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use knative::{
+        conditions::ConditionAccessor,
+        source_types::SinkManager,
+        status_types::HasStatus,
+    };
+
+    #[test]
+    fn deserializes_ping_source() {
+        let source: PingSource = serde_yaml::from_str(r#"
+apiVersion: sources.knative.dev/v1
+kind: PingSource
+metadata:
+  name: test-ping-source
+spec:
+  schedule: "*/1 * * * *"
+  contentType: "application/json"
+  data: '{"message": "Hello world!"}'
+  sink:
+    ref:
+      apiVersion: serving.knative.dev/v1
+      kind: Service
+      name: event-display
+"#).unwrap();
+
+        let spec = &source.spec;
+        assert_eq!(spec.schedule, "*/1 * * * *");
+        assert_eq!(spec.content_type.as_deref(), Some("application/json"));
+        assert_eq!(spec.data.as_deref(), Some(r#"{"message": "Hello world!"}"#));
+        assert_eq!(spec.data_base64, None);
+        assert!(spec.timezone.is_none());
+        assert!(spec.source_spec.validate().is_ok());
+        assert_eq!(spec.validate(), Ok(()));
+
+        let json = serde_json::to_value(spec).unwrap();
+        assert_eq!(json["contentType"], "application/json");
+        assert!(json.get("dataBase64").is_none());
+    }
+
+    #[test]
+    fn schedule_defaults_to_every_minute() {
+        let spec: PingSourceSpec = serde_json::from_value(serde_json::json!({
+            "data": "hello",
+            "sink": { "uri": "http://event-display.default.svc.cluster.local" }
+        })).unwrap();
+        assert_eq!(spec.schedule, "* * * * *");
+    }
+
+    #[test]
+    fn requires_exactly_one_of_data() {
+        let spec = |data: Option<&str>, data_base64: Option<&str>| PingSourceSpec::new(
+            "* * * * *",
+            SourceSpec::default(),
+            data.map(String::from),
+            data_base64.map(String::from),
+        );
+
+        assert!(spec(Some("hello"), None).is_ok());
+        assert!(spec(None, Some("aGVsbG8=")).is_ok());
+        assert_eq!(spec(None, None).unwrap_err(), PingSourceErr::NoData);
+        assert_eq!(spec(Some("hello"), Some("aGVsbG8=")).unwrap_err(), PingSourceErr::BothData);
+    }
+
+    #[test]
+    fn can_mark_sink_on_ping_source() {
+        let spec = PingSourceSpec::new("* * * * *", SourceSpec::default(), Some("hello".into()), None).unwrap();
+        let mut source = PingSource::new("ping", spec);
+        let status = source.status_mut();
+        assert!(!status.is_ready());
+        status.mark_sink("http://event-display.default.svc.cluster.local/".parse().unwrap());
+        assert!(status.is_ready());
+    }
+}
@@ -17,7 +17,11 @@ use serde::{Deserialize, Serialize};
     group = "sources.knative.dev",
     status = "SinkBindingStatus",
     version = "v1alpha2",
-    namespaced
+    namespaced,
+    printcolumn = r#"{"name":"Sink", "type":"string", "jsonPath":".status.sinkUri"}"#,
+    printcolumn = r#"{"name":"Ready", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].status"}"#,
+    printcolumn = r#"{"name":"Reason", "type":"string", "jsonPath":".status.conditions[?(@.type==\"Ready\")].reason"}"#,
+    printcolumn = r#"{"name":"Age", "type":"date", "jsonPath":".metadata.creationTimestamp"}"#
 )]
 #[serde(rename_all = "camelCase")]
 pub struct SinkBindingSpec {
@@ -30,7 +34,7 @@ pub struct SinkBindingSpec {
 }
 
 /// Communicates the observed state of the [`SinkBinding`] (from the controller).
-#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
 pub struct SinkBindingStatus {
     /// inherits [`SourceStatus`], which currently provides:
     /// * observed_generation
@@ -39,3 +43,157 @@ pub struct SinkBindingStatus {
     #[serde(flatten)]
     pub source_status: SourceStatus<SourceCondition>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use knative::{
+        conditions::ConditionAccessor,
+        source_types::SinkManager,
+        status_types::{FieldManager, HasStatus},
+    };
+
+    #[test]
+    fn can_mark_sink_on_sink_binding() {
+        let mut sink_binding = SinkBinding::new("binding", SinkBindingSpec {
+            source_spec: SourceSpec::default(),
+            binding_spec: BindingSpec::default(),
+        });
+        assert!(sink_binding.status.is_none());
+
+        let status = sink_binding.status_mut();
+        assert!(!status.is_ready());
+
+        status.mark_sink("http://sink.default.svc.cluster.local/".parse().unwrap());
+        assert!(status.is_ready());
+        assert_eq!(
+            sink_binding.status.unwrap().source_status.sink_uri.map(String::from).as_deref(),
+            Some("http://sink.default.svc.cluster.local/")
+        );
+    }
+
+    #[test]
+    fn can_mark_no_sink_on_sink_binding() {
+        let mut status = SinkBindingStatus::default();
+        status.mark_sink("http://sink.default.svc.cluster.local/".parse().unwrap());
+        assert!(status.is_ready());
+
+        status.mark_no_sink("NotFound", Some("sink was deleted".into()));
+        assert!(!status.is_ready());
+        assert!(status.source_status.sink_uri.is_none());
+    }
+
+    #[test]
+    fn sink_binding_has_distinct_field_manager() {
+        assert_eq!(SinkBinding::FIELD_MANAGER, "sinkbinding-controller.knative-rs");
+    }
+
+    #[test]
+    fn converts_from_dynamic_object() {
+        use crate::error::Error;
+        use kube::api::DynamicObject;
+
+        let obj: DynamicObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "sources.knative.dev/v1alpha2",
+            "kind": "SinkBinding",
+            "metadata": { "name": "binding", "namespace": "default" },
+            "spec": {
+                "sink": { "uri": "http://sink.default.svc.cluster.local/" },
+                "subject": { "apiVersion": "apps/v1", "kind": "Deployment", "name": "app" },
+            },
+        })).unwrap();
+
+        let sink_binding = SinkBinding::try_from(obj.clone()).unwrap();
+        assert_eq!(sink_binding.metadata.name.as_deref(), Some("binding"));
+        assert_eq!(sink_binding.spec.binding_spec.subject.kind.as_deref(), Some("Deployment"));
+
+        let mut trigger = obj.clone();
+        trigger.types.as_mut().unwrap().kind = "Trigger".into();
+        let err = SinkBinding::try_from(trigger).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::TypeMismatch { ref expected, ref actual }
+                if expected == "sources.knative.dev/v1alpha2/SinkBinding"
+                    && actual == "sources.knative.dev/v1alpha2/Trigger"
+        ), "{err}");
+
+        let mut malformed = obj;
+        malformed.data["spec"]["subject"] = serde_json::json!("app");
+        assert!(matches!(SinkBinding::try_from(malformed), Err(Error::SerdeError(_))));
+    }
+
+    #[test]
+    fn deserializes_absent_empty_and_partial_status() {
+        use serde_json::json;
+        let sink_binding = |status: Option<serde_json::Value>| {
+            let mut obj = json!({
+                "apiVersion": "sources.knative.dev/v1alpha2",
+                "kind": "SinkBinding",
+                "metadata": { "name": "binding" },
+                "spec": { "subject": { "apiVersion": "apps/v1", "kind": "Deployment", "name": "app" } },
+            });
+            if let Some(status) = status {
+                obj["status"] = status;
+            }
+            serde_json::from_value::<SinkBinding>(obj).unwrap()
+        };
+
+        assert!(sink_binding(None).status.is_none());
+
+        let mut empty = sink_binding(Some(json!({})));
+        let status = empty.status.as_mut().unwrap();
+        assert_eq!(status.source_status.status.observed_generation, None);
+        assert_eq!(status.source_status.sink_uri, None);
+        assert!(status.source_status.cloud_event_attributes.is_none());
+        assert!(!status.is_ready());
+
+        let mut partial = sink_binding(Some(json!({
+            "observedGeneration": 2,
+            "sinkUri": "http://sink.default.svc.cluster.local/",
+            "conditions": [{ "type": "SinkProvided", "status": "True" }],
+        })));
+        let status = partial.status.as_mut().unwrap();
+        assert_eq!(status.source_status.status.observed_generation, Some(2));
+        assert_eq!(
+            status.source_status.sink_uri.as_ref().map(url::Url::as_str),
+            Some("http://sink.default.svc.cluster.local/")
+        );
+        assert!(status.source_status.status.annotations.is_none());
+        // the absent happy condition is not yet known
+        assert!(!status.is_ready());
+        assert!(status.manager().get_condition(SourceCondition::SinkProvided).unwrap().is_true());
+    }
+
+    #[test]
+    fn reads_status_generically() {
+        use knative::status_types::HasStatus;
+
+        fn sink_uri<K>(resource: &K) -> Option<String>
+        where
+            K: HasStatus<Status = SinkBindingStatus>,
+        {
+            resource.status()?.source_status.sink_uri.as_ref().map(|u| u.to_string())
+        }
+
+        let mut sink_binding = SinkBinding::new("binding", SinkBindingSpec {
+            source_spec: SourceSpec::default(),
+            binding_spec: BindingSpec::default(),
+        });
+        assert_eq!(sink_uri(&sink_binding), None);
+
+        HasStatus::status_mut(&mut sink_binding).mark_sink("http://sink.default.svc/".parse().unwrap());
+        assert_eq!(sink_uri(&sink_binding).as_deref(), Some("http://sink.default.svc/"));
+    }
+
+    #[test]
+    fn crd_prints_ready_column() {
+        use kube::CustomResourceExt;
+
+        let crd = SinkBinding::crd();
+        let columns = crd.spec.versions[0].additional_printer_columns.as_ref().unwrap();
+        let ready = columns.iter().find(|c| c.name == "Ready").expect("ready column");
+        assert_eq!(ready.json_path, r#".status.conditions[?(@.type=="Ready")].status"#);
+        let names = columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["Sink", "Ready", "Reason", "Age"]);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::apis::eventing::v1::trigger::{Trigger, TriggerStatus};
+use crate::apis::flows::v1::parallel::{Parallel, ParallelStatus};
+use crate::apis::messaging::v1::subscription::{Subscription, SubscriptionStatus};
+use crate::apis::sources::v1::apiserversource::{ApiServerSource, ApiServerSourceStatus};
+use crate::apis::sources::v1::containersource::{ContainerSource, ContainerSourceStatus};
+use crate::apis::sources::v1::pingsource::{PingSource, PingSourceStatus};
+use crate::apis::sources::v1::sinkbinding::{SinkBinding, SinkBindingStatus};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use crate::error::Error;
+use knative::{
+    conditions::{ConditionAccessor, Conditions},
+    source::SourceDuck,
+    source_types::{SinkManager, SourceCondition, SourceSpec, SourceStatus},
+    status_types::{FieldManager, HasStatus},
+};
+use kube::api::DynamicObject;
+use kube::{CustomResourceExt, Resource};
+
+/// Implements convenience constructors of a typed [`kube::Api`] on each resource.
+///
+/// ```no_run
+/// # async fn doc(client: kube::Client) {
+/// use knative_eventing::apis::eventing::v1::trigger::Trigger;
+///
+/// let triggers = Trigger::api(client.clone(), "default");
+/// let all_triggers = Trigger::api_all(client);
+/// # }
+/// ```
+macro_rules! impl_api {
+    ($($kind:ty),* $(,)?) => {$(
+        impl $kind {
+            #[doc = concat!("Returns an [`Api`](kube::Api) of [`", stringify!($kind), "`] in `namespace`.")]
+            pub fn api(client: kube::Client, namespace: &str) -> kube::Api<Self> {
+                kube::Api::namespaced(client, namespace)
+            }
+
+            #[doc = concat!("Returns an [`Api`](kube::Api) of [`", stringify!($kind), "`] in all namespaces.")]
+            pub fn api_all(client: kube::Client) -> kube::Api<Self> {
+                kube::Api::all(client)
+            }
+        }
+    )*};
+}
+
+impl_api!(ApiServerSource, ContainerSource, Parallel, PingSource, SinkBinding, Subscription, Trigger);
+
+/// Implements [`TryFrom<DynamicObject>`](DynamicObject) on each resource, so that objects received
+/// from a dynamic informer can be converted to their typed resource.
+///
+/// The `apiVersion` and `kind` of the object must match the resource.
+macro_rules! impl_try_from_dynamic {
+    ($($kind:ty),* $(,)?) => {$(
+        impl TryFrom<DynamicObject> for $kind {
+            type Error = Error;
+
+            fn try_from(obj: DynamicObject) -> Result<Self, Self::Error> {
+                check_type::<Self>(&obj)?;
+                Ok(serde_json::from_value(serde_json::to_value(obj)?)?)
+            }
+        }
+    )*};
+}
+
+impl_try_from_dynamic!(ApiServerSource, ContainerSource, Parallel, PingSource, SinkBinding, Subscription, Trigger);
+
+/// Implements [`HasStatus`] on each resource with a status.
+macro_rules! impl_has_status {
+    ($($kind:ty => $status:ty),* $(,)?) => {$(
+        impl HasStatus for $kind {
+            type Status = $status;
+
+            fn status(&self) -> Option<&Self::Status> {
+                self.status.as_ref()
+            }
+
+            fn status_mut(&mut self) -> &mut Self::Status {
+                self.status.get_or_insert_with(Default::default)
+            }
+        }
+    )*};
+}
+
+impl_has_status!(
+    ApiServerSource => ApiServerSourceStatus,
+    ContainerSource => ContainerSourceStatus,
+    Parallel => ParallelStatus,
+    PingSource => PingSourceStatus,
+    SinkBinding => SinkBindingStatus,
+    Subscription => SubscriptionStatus,
+    Trigger => TriggerStatus,
+);
+
+/// Implements the source duck type on each source, whose spec and status flatten a [`SourceSpec`]
+/// and [`SourceStatus`], along with the field manager of its controller.
+macro_rules! impl_source {
+    ($($kind:ty => $status:ty, $field_manager:literal),* $(,)?) => {$(
+        impl ConditionAccessor<SourceCondition> for $status {
+            fn conditions(&mut self) -> &mut Conditions<SourceCondition> {
+                self.source_status.conditions()
+            }
+        }
+
+        impl SinkManager<SourceCondition> for $status {
+            fn source_status(&mut self) -> &mut SourceStatus<SourceCondition> {
+                &mut self.source_status
+            }
+        }
+
+        impl SourceDuck for $kind {
+            type Condition = SourceCondition;
+
+            fn source_spec(&self) -> &SourceSpec {
+                &self.spec.source_spec
+            }
+
+            fn source_status_mut(&mut self) -> &mut SourceStatus<SourceCondition> {
+                &mut self.status_mut().source_status
+            }
+        }
+
+        impl FieldManager for $kind {
+            const FIELD_MANAGER: &'static str = $field_manager;
+        }
+    )*};
+}
+
+impl_source!(
+    ApiServerSource => ApiServerSourceStatus, "apiserversource-controller.knative-rs",
+    ContainerSource => ContainerSourceStatus, "containersource-controller.knative-rs",
+    PingSource => PingSourceStatus, "pingsource-controller.knative-rs",
+    SinkBinding => SinkBindingStatus, "sinkbinding-controller.knative-rs",
+);
+
+fn check_type<K: Resource<DynamicType = ()>>(obj: &DynamicObject) -> Result<(), Error> {
+    let expected = format!("{}/{}", K::api_version(&()), K::kind(&()));
+    let actual = obj.types.as_ref()
+        .map(|t| format!("{}/{}", t.api_version, t.kind))
+        .unwrap_or_default();
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::TypeMismatch { expected, actual })
+    }
+}
+
+/// Returns the [`CustomResourceDefinition`] of every resource provided by this crate.
+pub fn crds() -> Vec<CustomResourceDefinition> {
+    vec![
+        ApiServerSource::crd(),
+        ContainerSource::crd(),
+        Parallel::crd(),
+        PingSource::crd(),
+        SinkBinding::crd(),
+        Subscription::crd(),
+        Trigger::crd(),
+    ]
+}
+
+/// Server-side apply every [`CustomResourceDefinition`] from [`crds()`], waiting for each to
+/// become established.
+///
+/// Applying is idempotent, so this is safe to call on every operator startup.
+#[cfg(feature = "runtime")]
+pub async fn apply_crds(client: &kube::Client) -> Result<(), Error> {
+    use kube::api::{Api, Patch, PatchParams};
+    use knative::status_types::DEFAULT_FIELD_MANAGER;
+    use kube::runtime::wait::{await_condition, conditions};
+
+    let api = Api::<CustomResourceDefinition>::all(client.clone());
+    let params = PatchParams::apply(DEFAULT_FIELD_MANAGER).force();
+
+    for crd in crds() {
+        let name = crd.metadata.name.clone().unwrap_or_default();
+        api.patch(&name, &params, &Patch::Apply(&crd)).await?;
+        await_condition(api.clone(), &name, conditions::is_crd_established()).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "runtime"))]
+mod test {
+    use super::*;
+    use http::{Method, Request, Response};
+    use hyper::Body;
+    use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::{
+        CustomResourceDefinitionCondition,
+        CustomResourceDefinitionStatus,
+    };
+    use std::sync::{Arc, Mutex};
+
+    fn established(mut crd: CustomResourceDefinition) -> CustomResourceDefinition {
+        crd.status = Some(CustomResourceDefinitionStatus {
+            conditions: Some(vec![CustomResourceDefinitionCondition {
+                type_: "Established".into(),
+                status: "True".into(),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+        crd
+    }
+
+    #[tokio::test]
+    async fn applies_all_crds() {
+        let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        let patched = Arc::new(Mutex::new(Vec::new()));
+        let log = patched.clone();
+
+        tokio::spawn(async move {
+            while let Some((request, send)) = handle.next_request().await {
+                let body = match *request.method() {
+                    Method::PATCH => {
+                        let name = request.uri().path().rsplit('/').next().unwrap().to_string();
+                        assert!(request.uri().query().unwrap().contains("fieldManager=knative-rs"));
+                        log.lock().unwrap().push(name.clone());
+                        let crd = crds().into_iter().find(|c| c.metadata.name == Some(name.clone())).unwrap();
+                        serde_json::to_value(established(crd)).unwrap()
+                    }
+                    // the list and watch requested while awaiting establishment
+                    _ => serde_json::json!({
+                        "apiVersion": "apiextensions.k8s.io/v1",
+                        "kind": "CustomResourceDefinitionList",
+                        "metadata": { "resourceVersion": "1" },
+                        "items": crds().into_iter().map(established).collect::<Vec<_>>(),
+                    }),
+                };
+                send.send_response(Response::new(Body::from(serde_json::to_vec(&body).unwrap())));
+            }
+        });
+        let client = kube::Client::new(service, "default");
+
+        apply_crds(&client).await.expect("crds are applied");
+        // applying again is a no-op
+        apply_crds(&client).await.expect("crds are applied again");
+
+        let expected = crds().into_iter().filter_map(|c| c.metadata.name).collect::<Vec<_>>();
+        assert_eq!(expected, vec![
+            "apiserversources.sources.knative.dev",
+            "containersources.sources.knative.dev",
+            "parallels.flows.knative.dev",
+            "pingsources.sources.knative.dev",
+            "sinkbindings.sources.knative.dev",
+            "subscriptions.messaging.knative.dev",
+            "triggers.eventing.knative.dev",
+        ]);
+        assert_eq!(*patched.lock().unwrap(), [expected.clone(), expected].concat());
+    }
+}
@@ -0,0 +1,22 @@
+use thiserror::Error;
+use kube::error::Error as KubeError;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Kube errors
+    #[error("Error: {0}")]
+    KubeError(#[from] KubeError),
+    /// Knative errors, which carry their own prefix
+    #[error(transparent)]
+    KnativeError(#[from] knative::error::Error),
+    /// Errors converting a dynamic object into a typed resource of a different type
+    #[error("Error: expected {expected}, got {actual}")]
+    TypeMismatch { expected: String, actual: String },
+    /// Errors deserializing a resource
+    #[error("Error deserializing: {0}")]
+    SerdeError(#[from] serde_json::Error),
+    /// Errors waiting on a resource condition
+    #[cfg(feature = "runtime")]
+    #[error("Error waiting: {0}")]
+    WaitError(#[from] kube::runtime::wait::Error),
+}
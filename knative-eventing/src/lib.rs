@@ -1 +1,7 @@
 pub mod apis;
+mod crds;
+pub mod error;
+
+pub use crds::crds;
+#[cfg(feature = "runtime")]
+pub use crds::apply_crds;
@@ -5,7 +5,8 @@ use kube::api::{Resource, ResourceExt};
 use thiserror::Error;
 use url::Url;
 use serde_json::Value;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 #[derive(Error, Debug)]
 pub enum AddressableErr {
@@ -13,8 +14,12 @@ pub enum AddressableErr {
     NotAddressable(String, String),
     #[error("url missing in address of {0}")]
     UrlNotSet(String),
+    #[error("{0} has no address named {1}")]
+    AddressNotFound(String, String),
     #[error("service must have name to be addressable")]
     ServiceMustHaveName,
+    /// Addresses never infer a kube config. These wrap the errors of callers that infer one to
+    /// pass to [`AddressableTypeExt::address_with`].
     #[error("unable to infer Kubeconfig: {0}")]
     InferConfigErr(#[from] kube::config::InferConfigError),
     #[error("unable to find Kubeconfig: {0}")]
@@ -23,14 +28,39 @@ pub enum AddressableErr {
     UrlParseErr(#[from] url::ParseError)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
 pub struct Addressable {
-    pub url: Option<Url>
+    /// Name of the address, set when the address is one of several named addresses.
+    pub name: Option<String>,
+    pub url: Option<Url>,
+    /// CACerts is the Certification Authority (CA) certificates in PEM format
+    /// that the source trusts when sending events to the sink.
+    #[serde(rename = "CACerts", skip_serializing_if = "Option::is_none")]
+    pub ca_certs: Option<String>,
+    /// Audience is the OIDC audience for this address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub struct AddressableStatus {
+    #[serde(default)]
     pub address: Addressable,
+    /// All addresses of a resource that exposes several, such as one per protocol.
+    #[serde(default)]
+    pub addresses: Vec<Addressable>,
+}
+
+impl AddressableStatus {
+    /// The address with `name`, falling back to `address` for resources that do not
+    /// expose `addresses`.
+    fn named(&self, name: &str) -> Option<&Addressable> {
+        match self.addresses.is_empty() {
+            false => self.addresses.iter().find(|a| a.name.as_deref() == Some(name)),
+            true => Some(&self.address)
+                .filter(|a| a.name.is_none() || a.name.as_deref() == Some(name)),
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -38,94 +68,314 @@ pub struct AddressableType {
     pub status: AddressableStatus
 }
 
+impl AddressableType {
+    /// Build the address of a [`Service`] from its name, namespace and port alone. No kube config
+    /// is read, so this works the same from a local kubeconfig and in cluster.
+    pub fn from_service(service: Service) -> Result<Self, AddressableErr> {
+        let address = service_address(&service, "default")?;
+        Ok(AddressableType {
+            status: AddressableStatus { address, addresses: Vec::new() }
+        })
+    }
+}
+
+/// Normalize a resolved url so that it compares equal across reconciles. Default ports
+/// (`:80` for http, `:443` for https) are removed and an empty path becomes `/`.
+pub fn normalize_url(mut url: Url) -> Url {
+    let default_port = match url.scheme() {
+        "http" => Some(80),
+        "https" => Some(443),
+        _ => None,
+    };
+    if url.port().is_some() && url.port() == default_port {
+        // only fails for urls that cannot have a port, which have no port to remove
+        let _ = url.set_port(None);
+    }
+    if url.path().is_empty() && !url.cannot_be_a_base() {
+        url.set_path("/");
+    }
+    url
+}
+
+/// The environment variable that overrides the cluster domain of service urls.
+pub const CLUSTER_DOMAIN_ENV: &str = "CLUSTER_DOMAIN";
+
+/// The cluster domain used when [`CLUSTER_DOMAIN_ENV`] is not set.
+pub const DEFAULT_CLUSTER_DOMAIN: &str = "cluster.local";
+
+/// The DNS domain of the cluster, read from the `CLUSTER_DOMAIN` environment variable and
+/// defaulting to `cluster.local`.
+pub fn cluster_domain() -> String {
+    std::env::var(CLUSTER_DOMAIN_ENV)
+        .ok()
+        .filter(|domain| !domain.is_empty())
+        .unwrap_or_else(|| DEFAULT_CLUSTER_DOMAIN.to_string())
+}
+
+/// The names of service ports that are preferred when a service exposes several.
+const HTTP_PORT_NAMES: &[&str] = &["http", "http2"];
+
+/// Select the port of a service from its `(name, port)` pairs. A port named `http` or `http2`
+/// is preferred, otherwise the first port is used.
+fn service_port<'a>(ports: impl Iterator<Item = (Option<&'a str>, i32)> + Clone) -> Option<i32> {
+    ports.clone()
+        .find(|(name, _)| matches!(name, Some(name) if HTTP_PORT_NAMES.contains(name)))
+        .or_else(|| ports.clone().next())
+        .map(|(_, port)| port)
+}
+
 #[doc(hidden)]
-/// Construct a url from the service metadata and kubeconfig
-async fn build_service_url(name: &str, namespace: &str) -> Result<Url, AddressableErr> {
-    let cluster_url = Config::infer().await?.cluster_url;
-    let scheme = cluster_url.scheme().unwrap_or(&http::uri::Scheme::HTTP);
-    let cluster_host = cluster_url.host().unwrap_or("cluster.local");
-    let url = Url::parse(&format!("{scheme}://{name}.{namespace}.svc.{cluster_host}"))?;
+/// Construct a url from the service metadata and port in the cluster domain of the environment.
+fn build_service_url(name: &str, namespace: &str, port: Option<i32>) -> Result<Url, AddressableErr> {
+    build_service_url_in(&cluster_domain(), name, namespace, port)
+}
+
+/// Construct a url from the service metadata and port in `cluster_domain`. Default ports are
+/// omitted from the url. Services are served over https only on port 443.
+fn build_service_url_in(
+    cluster_domain: &str,
+    name: &str,
+    namespace: &str,
+    port: Option<i32>
+) -> Result<Url, AddressableErr> {
+    let scheme = match port {
+        Some(443) => "https",
+        _ => "http",
+    };
+    let port = port.map(|p| format!(":{p}")).unwrap_or_default();
+    let url = Url::parse(&format!("{scheme}://{name}.{namespace}.svc.{cluster_domain}{port}"))?;
 
-    Ok(url)
+    Ok(normalize_url(url))
 }
 
 #[doc(hidden)]
-/// Parse a url from a &serde_json::Value containing a status, avoiding a clone of data
-fn parse_url_from_obj_data(name: &str, kind: &str, data: &Value) -> Result<Url, AddressableErr> {
-    if let Some(data) = data.as_object() {
-        if let Some(status) = data.get("status").and_then(Value::as_object) {
-            if let Some(address) = status.get("address").and_then(Value::as_object) {
-                return match address.get("url").and_then(Value::as_str).map(Url::parse) {
-                    Some(url) => Ok(url?),
-                    None => Err(AddressableErr::UrlNotSet(name.to_string()))
-                }
-            }
+/// Parse an [`Addressable`] from a &serde_json::Value containing a status, avoiding a clone of
+/// data.
+///
+/// Without an `address_name` the address is read from `status.address`, or the first of
+/// `status.addresses`. With one, the matching entry of `status.addresses` is used, falling back
+/// to `status.address` for resources that only expose a single address.
+fn parse_address_from_obj_data(
+    name: &str,
+    kind: &str,
+    data: &Value,
+    address_name: Option<&str>
+) -> Result<Addressable, AddressableErr> {
+    let status = match data.get("status").and_then(Value::as_object) {
+        Some(status) => status,
+        None => return Err(AddressableErr::NotAddressable(name.to_string(), kind.to_string())),
+    };
+    let single = status.get("address").and_then(Value::as_object);
+    let addresses = status.get("addresses")
+        .and_then(Value::as_array)
+        .filter(|addresses| !addresses.is_empty());
+    if single.is_none() && addresses.is_none() {
+        return Err(AddressableErr::NotAddressable(name.to_string(), kind.to_string()))
+    }
+
+    let address_has_name = |address: &serde_json::Map<String, Value>, address_name: &str| {
+        address.get("name").and_then(Value::as_str) == Some(address_name)
+    };
+    let address = match (address_name, addresses) {
+        (None, _) => single.or_else(|| addresses.and_then(|a| a[0].as_object())),
+        (Some(address_name), Some(addresses)) => addresses.iter()
+            .filter_map(Value::as_object)
+            .find(|address| address_has_name(address, address_name)),
+        (Some(address_name), None) => single.filter(|address| {
+            !address.contains_key("name") || address_has_name(address, address_name)
+        }),
+    };
+
+    let field = |address: &serde_json::Map<String, Value>, field: &str| {
+        address.get(field).and_then(Value::as_str).map(str::to_string)
+    };
+    match (address, address_name) {
+        (Some(address), _) => match address.get("url").and_then(Value::as_str).map(Url::parse) {
+            Some(url) => Ok(Addressable {
+                name: field(address, "name"),
+                url: Some(normalize_url(url?)),
+                ca_certs: field(address, "CACerts"),
+                audience: field(address, "audience"),
+            }),
+            None => Err(AddressableErr::UrlNotSet(name.to_string()))
+        },
+        (None, Some(address_name)) => {
+            Err(AddressableErr::AddressNotFound(name.to_string(), address_name.to_string()))
         }
+        (None, None) => Err(AddressableErr::UrlNotSet(name.to_string())),
+    }
+}
+
+/// The api version and kind of objects that advertise their public url at `status.url`, rather
+/// than `status.address.url`.
+const STATUS_URL_KINDS: &[(&str, &str)] = &[
+    ("serving.knative.dev/v1", "Service"),
+    ("serving.knative.dev/v1", "Route"),
+];
+
+#[doc(hidden)]
+/// Parse the public url from `status.url` of a &serde_json::Value, such as a Knative Service
+fn parse_status_url_from_obj_data(name: &str, kind: &str, data: &Value) -> Result<Url, AddressableErr> {
+    match (data.pointer("/status/url").and_then(Value::as_str), data.pointer("/status")) {
+        (Some(url), _) => Ok(normalize_url(Url::parse(url)?)),
+        (None, Some(Value::Object(_))) => Err(AddressableErr::UrlNotSet(name.to_string())),
+        (None, _) => Err(AddressableErr::NotAddressable(name.to_string(), kind.to_string())),
     }
-    Err(AddressableErr::NotAddressable(name.to_string(), kind.to_string()))
 }
 
 #[async_trait::async_trait]
 pub trait AddressableTypeExt {
-    async fn address(&self) -> Result<Url, AddressableErr>;
+    /// The full address, including the CA certificates and OIDC audience used to send
+    /// events to it. The url of the returned address is always set.
+    async fn addressable(&self) -> Result<Addressable, AddressableErr>;
+
+    async fn address(&self) -> Result<Url, AddressableErr> {
+        url_of(self.addressable().await?, "addressable")
+    }
+
+    /// The url of the address called `name`, for resources that expose several addresses
+    /// at `status.addresses`.
+    async fn address_named(&self, name: &str) -> Result<Url, AddressableErr>;
+
+    /// Like [`address`](AddressableTypeExt::address), but a [`Service`] without a namespace is
+    /// addressed in the default namespace of `config` rather than `default`. No config is
+    /// inferred, so callers resolving many addresses can infer one once and reuse it.
+    async fn address_with(&self, _config: &Config) -> Result<Url, AddressableErr> {
+        self.address().await
+    }
+}
+
+/// The url of an address, failing when it is not set.
+fn url_of(address: Addressable, name: &str) -> Result<Url, AddressableErr> {
+    address.url.ok_or_else(|| AddressableErr::UrlNotSet(name.to_string()))
+}
+
+/// Normalize the url of an address, failing when it is not set.
+fn with_url(mut address: Addressable, name: &str) -> Result<Addressable, AddressableErr> {
+    match address.url.take() {
+        Some(url) => Ok(Addressable { url: Some(normalize_url(url)), ..address }),
+        None => Err(AddressableErr::UrlNotSet(name.to_string())),
+    }
 }
 
 #[async_trait::async_trait]
 impl AddressableTypeExt for AddressableType {
-    async fn address(&self) -> Result<Url, AddressableErr> {
-        self.status.address.url
-            .clone()
-            .ok_or_else(|| AddressableErr::UrlNotSet("addressable".to_string()))
+    async fn addressable(&self) -> Result<Addressable, AddressableErr> {
+        let status = &self.status;
+        let address = match (&status.address.url, status.addresses.first()) {
+            (None, Some(first)) => first,
+            _ => &status.address,
+        };
+        with_url(address.clone(), "addressable")
+    }
+
+    async fn address_named(&self, name: &str) -> Result<Url, AddressableErr> {
+        let address = self.status.named(name)
+            .ok_or_else(|| AddressableErr::AddressNotFound("addressable".to_string(), name.to_string()))?;
+        url_of(with_url(address.clone(), "addressable")?, "addressable")
     }
 }
 
-#[async_trait::async_trait]
-impl AddressableTypeExt for DynamicObject {
-    async fn address(&self) -> Result<Url, AddressableErr> {
-        let name = self.meta().name.as_ref().ok_or(AddressableErr::ServiceMustHaveName)?;
-        let namespace = self.namespace().unwrap_or_else(|| "default".into());
+/// Resolve the address of a [`DynamicObject`], optionally selecting one of its named addresses.
+/// Services without a namespace are addressed in `default_namespace`.
+fn dynamic_object_address(
+    obj: &DynamicObject,
+    address_name: Option<&str>,
+    default_namespace: &str
+) -> Result<Addressable, AddressableErr> {
+    let name = obj.meta().name.as_ref().ok_or(AddressableErr::ServiceMustHaveName)?;
+    let namespace = obj.namespace().unwrap_or_else(|| default_namespace.into());
 
-        match &self.types {
-            Some(t) => match (t.api_version.as_ref(), t.kind.as_ref()) {
-                ("v1", "Service") => build_service_url(name, &namespace).await,
-                _ => parse_url_from_obj_data(name, t.kind.as_ref(), &self.data)
+    match &obj.types {
+        Some(t) => match (t.api_version.as_ref(), t.kind.as_ref(), address_name) {
+            ("v1", "Service", None) => {
+                let ports = obj.data.pointer("/spec/ports")
+                    .and_then(Value::as_array)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|p| {
+                        let port = p.get("port").and_then(Value::as_i64)?;
+                        Some((p.get("name").and_then(Value::as_str), i32::try_from(port).ok()?))
+                    });
+                Ok(Addressable {
+                    url: Some(build_service_url(name, &namespace, service_port(ports))?),
+                    ..Addressable::default()
+                })
+            }
+            ("v1", "Service", Some(address_name)) => {
+                Err(AddressableErr::AddressNotFound(name.to_string(), address_name.to_string()))
             }
-            None => Err(AddressableErr::NotAddressable(name.to_string(), "unknown".to_string()))
+            (api_version, kind, None) if STATUS_URL_KINDS.contains(&(api_version, kind)) => {
+                Ok(Addressable {
+                    url: Some(parse_status_url_from_obj_data(name, kind, &obj.data)?),
+                    ..Addressable::default()
+                })
+            }
+            (_, kind, address_name) => parse_address_from_obj_data(name, kind, &obj.data, address_name)
         }
+        None => Err(AddressableErr::NotAddressable(name.to_string(), "unknown".to_string()))
     }
 }
 
 #[async_trait::async_trait]
-impl AddressableTypeExt for Service {
-    async fn address(&self) -> Result<Url, AddressableErr> {
-        let name = self.meta().name.as_ref().ok_or(AddressableErr::ServiceMustHaveName)?;
-        let namespace = self.namespace().unwrap_or_else(|| "default".into());
-        build_service_url(name, &namespace).await
+impl AddressableTypeExt for DynamicObject {
+    async fn addressable(&self) -> Result<Addressable, AddressableErr> {
+        dynamic_object_address(self, None, "default")
+    }
+
+    async fn address_named(&self, name: &str) -> Result<Url, AddressableErr> {
+        let address = dynamic_object_address(self, Some(name), "default")?;
+        url_of(address, self.meta().name.as_deref().unwrap_or_default())
+    }
+
+    async fn address_with(&self, config: &Config) -> Result<Url, AddressableErr> {
+        let address = dynamic_object_address(self, None, &config.default_namespace)?;
+        url_of(address, self.meta().name.as_deref().unwrap_or_default())
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
+/// Resolve the address of a [`Service`], in `default_namespace` if it has no namespace.
+fn service_address(service: &Service, default_namespace: &str) -> Result<Addressable, AddressableErr> {
+    let name = service.meta().name.as_ref().ok_or(AddressableErr::ServiceMustHaveName)?;
+    let namespace = service.namespace().unwrap_or_else(|| default_namespace.into());
+    let ports = service.spec.iter()
+        .flat_map(|spec| spec.ports.iter().flatten())
+        .map(|p| (p.name.as_deref(), p.port));
+    Ok(Addressable {
+        url: Some(build_service_url(name, &namespace, service_port(ports))?),
+        ..Addressable::default()
+    })
+}
 
-    use serde::de::DeserializeOwned;
-    use std::fs;
+#[async_trait::async_trait]
+impl AddressableTypeExt for Service {
+    async fn addressable(&self) -> Result<Addressable, AddressableErr> {
+        service_address(self, "default")
+    }
 
-    fn mock_path() -> String {
-        format!("{}/{}/",
-            env!("CARGO_MANIFEST_DIR"),
-            "../test/mock",
-        )
+    /// Services only have a single, unnamed address.
+    async fn address_named(&self, name: &str) -> Result<Url, AddressableErr> {
+        let service = self.meta().name.as_ref().ok_or(AddressableErr::ServiceMustHaveName)?;
+        Err(AddressableErr::AddressNotFound(service.to_string(), name.to_string()))
     }
 
-    fn setup_kubeconfig() {
-        std::env::set_var("KUBECONFIG", mock_path() + "kubeconfig.yaml");
+    async fn address_with(&self, config: &Config) -> Result<Url, AddressableErr> {
+        let address = service_address(self, &config.default_namespace)?;
+        url_of(address, self.meta().name.as_deref().unwrap_or_default())
     }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    fn read_mock<T: Resource + DeserializeOwned>(filename: &str) -> T {
-        let path = mock_path() + filename;
-        let yaml = fs::read_to_string(path).expect("path to mock");
-        serde_yaml::from_str(&yaml).unwrap()
+    use crate::mock::read_mock;
+
+    #[test]
+    fn addressable_url_schemas_as_uri() {
+        let schema = serde_json::to_value(schemars::schema_for!(Addressable)).unwrap();
+        assert_eq!(schema["properties"]["url"]["format"], "uri");
     }
 
     #[async_std::test]
@@ -149,9 +399,140 @@ mod test {
         assert_eq!(uri.path(), "/default/default");
     }
 
+    #[async_std::test]
+    async fn knative_service_uri() {
+        let service = read_mock::<DynamicObject>("knative_service.yaml");
+        let uri = service.address().await.expect("knative service is addressable");
+        assert_eq!(uri.as_str(), "https://hello.default.example.com/");
+    }
+
+    #[async_std::test]
+    async fn knative_route_uri() {
+        let route = read_mock::<DynamicObject>("knative_route.yaml");
+        let uri = route.address().await.expect("knative route is addressable");
+        assert_eq!(uri.as_str(), "https://hello.default.example.com/");
+    }
+
+    #[test]
+    fn normalize_strips_default_ports() {
+        let url = normalize_url(Url::parse("http://sink.default.svc:80/path").unwrap());
+        assert_eq!(url.port(), None);
+        assert_eq!(url.as_str(), "http://sink.default.svc/path");
+
+        let url = normalize_url(Url::parse("https://sink.default.svc:443").unwrap());
+        assert_eq!(url.as_str(), "https://sink.default.svc/");
+
+        // non-default ports are kept
+        let url = normalize_url(Url::parse("http://sink.default.svc:8080").unwrap());
+        assert_eq!(url.as_str(), "http://sink.default.svc:8080/");
+    }
+
+    #[test]
+    fn normalize_sets_empty_path() {
+        let url = normalize_url(Url::parse("kafka://sink.default.svc").unwrap());
+        assert_eq!(url.path(), "/");
+        assert_eq!(url.as_str(), "kafka://sink.default.svc/");
+    }
+
+    #[async_std::test]
+    async fn addressable_url_is_normalized() {
+        let addressable: AddressableType = serde_json::from_value(serde_json::json!({
+            "status": { "address": { "url": "https://sink.default.svc:443" } }
+        })).unwrap();
+        let url = addressable.address().await.unwrap();
+        assert_eq!(url.as_str(), "https://sink.default.svc/");
+    }
+
+    #[async_std::test]
+    async fn address_without_url_is_an_error() {
+        let addressable: AddressableType = serde_json::from_value(serde_json::json!({
+            "status": { "address": { "name": "http" } }
+        })).unwrap();
+        assert!(matches!(addressable.address().await, Err(AddressableErr::UrlNotSet(_))));
+        assert!(matches!(addressable.address_named("http").await, Err(AddressableErr::UrlNotSet(_))));
+
+        // implementors that return an address without a url do not panic
+        struct NoUrl;
+
+        #[async_trait::async_trait]
+        impl AddressableTypeExt for NoUrl {
+            async fn addressable(&self) -> Result<Addressable, AddressableErr> {
+                Ok(Addressable::default())
+            }
+
+            async fn address_named(&self, _name: &str) -> Result<Url, AddressableErr> {
+                url_of(Addressable::default(), "no-url")
+            }
+        }
+        assert!(matches!(NoUrl.address().await, Err(AddressableErr::UrlNotSet(_))));
+    }
+
+    #[test]
+    fn named_address_deserializes_into_addressable() {
+        let broker = read_mock::<DynamicObject>("named_broker.yaml");
+        let addressable: AddressableType = serde_json::from_value(broker.data)
+            .expect("broker status deserializes into AddressableType");
+        assert_eq!(addressable.status.address.name.as_deref(), Some("http"));
+        assert_eq!(
+            addressable.status.address.url.unwrap().as_str(),
+            "http://broker-ingress.knative-eventing.svc.cluster.local/default/named"
+        );
+    }
+
+    #[async_std::test]
+    async fn selects_named_address() {
+        let broker = read_mock::<DynamicObject>("multi_address_broker.yaml");
+        let uri = broker.address_named("https").await.expect("broker has an https address");
+        assert_eq!(uri.as_str(), "https://broker-ingress.knative-eventing.svc.cluster.local/default/multi");
+        let uri = broker.address().await.expect("broker is addressable");
+        assert_eq!(uri.scheme(), "http");
+        assert!(matches!(
+            broker.address_named("grpc").await,
+            Err(AddressableErr::AddressNotFound(name, address)) if name == "multi" && address == "grpc"
+        ));
+
+        let addressable: AddressableType = serde_json::from_value(broker.data)
+            .expect("broker status deserializes into AddressableType");
+        assert_eq!(addressable.status.addresses.len(), 2);
+        let uri = addressable.address_named("https").await.expect("https address is set");
+        assert_eq!(uri.scheme(), "https");
+    }
+
+    #[async_std::test]
+    async fn addressable_includes_ca_certs_and_audience() {
+        let broker = read_mock::<DynamicObject>("multi_address_broker.yaml");
+        let address = broker.addressable().await.expect("broker is addressable");
+        assert_eq!(address.name.as_deref(), Some("http"));
+        assert_eq!(address.audience.as_deref(), Some("eventing.knative.dev/broker/default/multi"));
+        assert_eq!(address.ca_certs, None);
+        assert_eq!(address.url, Some(broker.address().await.unwrap()));
+
+        let addressable: AddressableType = serde_json::from_value(broker.data)
+            .expect("broker status deserializes into AddressableType");
+        let https = &addressable.status.addresses[1];
+        assert!(https.ca_certs.as_deref().unwrap().starts_with("-----BEGIN CERTIFICATE-----"));
+        let address = addressable.addressable().await.unwrap();
+        assert_eq!(address.audience.as_deref(), Some("eventing.knative.dev/broker/default/multi"));
+    }
+
+    #[async_std::test]
+    async fn named_address_falls_back_to_single_address() {
+        let broker = read_mock::<DynamicObject>("named_broker.yaml");
+        let uri = broker.address_named("http").await.expect("single address is named http");
+        assert_eq!(uri.path(), "/default/named");
+        assert!(matches!(
+            broker.address_named("https").await,
+            Err(AddressableErr::AddressNotFound(..))
+        ));
+
+        // unnamed addresses satisfy any name
+        let broker = read_mock::<DynamicObject>("default_broker.yaml");
+        let uri = broker.address_named("http").await.expect("single address is unnamed");
+        assert_eq!(uri.path(), "/default/default");
+    }
+
     #[async_std::test]
     async fn service_uri() {
-        setup_kubeconfig();
         let service = read_mock::<DynamicObject>("default_service.yaml");
         let uri = service.address().await.expect("to read config");
         assert_eq!(uri.scheme(), "http");
@@ -161,12 +542,92 @@ mod test {
 
     #[async_std::test]
     async fn service_struct_uri() {
-        setup_kubeconfig();
         let service = read_mock::<Service>("default_service.yaml");
         let uri = service.address().await.expect("");
         assert_eq!(uri.scheme(), "http");
         assert_eq!(uri.host().unwrap().to_string(), "default.default.svc.cluster.local");
         assert_eq!(uri.path(), "/");
     }
-}
 
+    #[async_std::test]
+    async fn service_uri_includes_non_default_port() {
+        let service = read_mock::<Service>("port_service.yaml");
+        let uri = service.address().await.expect("service is addressable");
+        assert_eq!(uri.as_str(), "http://ports.default.svc.cluster.local:8080/");
+
+        let service = read_mock::<DynamicObject>("port_service.yaml");
+        let uri = service.address().await.expect("service object is addressable");
+        assert_eq!(uri.as_str(), "http://ports.default.svc.cluster.local:8080/");
+    }
+
+    #[test]
+    fn prefers_http_service_ports() {
+        let ports = [(Some("metrics"), 9090), (Some("http2"), 8080)];
+        assert_eq!(service_port(ports.into_iter()), Some(8080));
+        let ports = [(Some("metrics"), 9090), (None, 8080)];
+        assert_eq!(service_port(ports.into_iter()), Some(9090));
+        assert_eq!(service_port(std::iter::empty()), None);
+    }
+
+    #[async_std::test]
+    async fn service_uri_scheme_follows_port() {
+        let mut service = read_mock::<DynamicObject>("default_service.yaml");
+        let uri = service.address().await.expect("service is addressable");
+        assert_eq!(uri.as_str(), "http://default.default.svc.cluster.local/");
+
+        service.data["spec"]["ports"][0]["port"] = 443.into();
+        let uri = service.address().await.expect("service is addressable");
+        assert_eq!(uri.as_str(), "https://default.default.svc.cluster.local/");
+    }
+
+    #[async_std::test]
+    async fn service_uri_with_config_uses_its_namespace() {
+        // a config for a cluster that does not exist, so nothing can have been inferred from it
+        let mut config = Config::new("https://fake.cluster.local".parse().unwrap());
+        config.default_namespace = "fake".into();
+
+        let mut service = read_mock::<Service>("default_service.yaml");
+        let uri = service.address_with(&config).await.expect("service is addressable");
+        assert_eq!(uri.as_str(), "http://default.default.svc.cluster.local/");
+
+        service.metadata.namespace = None;
+        let uri = service.address_with(&config).await.expect("service is addressable");
+        assert_eq!(uri.as_str(), "http://default.fake.svc.cluster.local/");
+        let uri = service.address().await.expect("service is addressable");
+        assert_eq!(uri.as_str(), "http://default.default.svc.cluster.local/");
+
+        let mut object = read_mock::<DynamicObject>("default_service.yaml");
+        object.metadata.namespace = None;
+        let uri = object.address_with(&config).await.expect("service object is addressable");
+        assert_eq!(uri.as_str(), "http://default.fake.svc.cluster.local/");
+
+        // addresses that are read from the status do not depend on the config
+        let broker = read_mock::<DynamicObject>("default_broker.yaml");
+        assert_eq!(broker.address_with(&config).await.unwrap(), broker.address().await.unwrap());
+    }
+
+    #[async_std::test]
+    async fn addressable_type_from_service() {
+        let service = read_mock::<Service>("default_service.yaml");
+        let addressable = AddressableType::from_service(service.clone())
+            .expect("service is addressable");
+        let uri = addressable.address().await.unwrap();
+        assert_eq!(uri.as_str(), "http://default.default.svc.cluster.local/");
+        // the single address of the service, without certificates or an audience
+        assert_eq!(addressable.status.address.url, service.addressable().await.unwrap().url);
+        assert_eq!(addressable.status.address.ca_certs, None);
+        assert_eq!(addressable.status.address.audience, None);
+        assert!(addressable.status.addresses.is_empty());
+
+        let nameless = Service::default();
+        assert!(matches!(AddressableType::from_service(nameless), Err(AddressableErr::ServiceMustHaveName)));
+    }
+
+    #[test]
+    fn service_uri_uses_cluster_domain() {
+        let uri = build_service_url_in("cluster.example.internal", "default", "default", Some(80)).unwrap();
+        assert_eq!(uri.as_str(), "http://default.default.svc.cluster.example.internal/");
+        let uri = build_service_url_in("cluster.example.internal", "ports", "default", Some(8080)).unwrap();
+        assert_eq!(uri.as_str(), "http://ports.default.svc.cluster.example.internal:8080/");
+    }
+}
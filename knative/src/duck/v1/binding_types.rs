@@ -2,8 +2,19 @@ use k8s_openapi::{
     api::core::v1::ObjectReference,
     apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta},
 };
+use super::knative_reference::{discover, KRefErr};
+use super::status_types::{HasStatus, Status};
+use crate::derive::ConditionType;
+use crate::error::Error;
+use enumset::EnumSetType;
+use knative_conditions::{ConditionAccessor, Conditions};
+use kube::{
+    api::{DynamicObject, GroupVersionKind, ListParams},
+    Api,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +24,73 @@ pub struct Binding {
     #[serde(skip_serializing_if = "Option::is_none")]
     metadata: Option<ObjectMeta>,
     spec: BindingSpec,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<BindingStatus<BindingCondition>>,
+}
+
+/// A baseline [`ConditionType`] for [`BindingStatus`].
+///
+/// Custom conditions should implement [`BindingConditionType`] in order to be used by
+/// [`BindingStatus`].
+#[derive(ConditionType, EnumSetType, Deserialize, Serialize, Debug, JsonSchema)]
+pub enum BindingCondition {
+    Ready,
+    /// The subject of the binding has been resolved.
+    #[dependent]
+    SubjectResolved,
+}
+
+/// BindingStatus contains the Status of a Binding.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingStatus<S: BindingConditionType> {
+    /// inherits Status, which currently provides:
+    /// * ObservedGeneration - the 'Generation' of the Service that was last
+    ///   processed by the controller.
+    /// * Conditions - the latest available observations of a resource's current
+    ///   state.
+    #[serde(flatten)]
+    pub status: Status<S>,
+}
+
+impl<S: BindingConditionType> ConditionAccessor<S> for BindingStatus<S> {
+    fn conditions(&mut self) -> &mut Conditions<S> {
+        self.status.conditions()
+    }
+}
+
+/// Provides management of the subject of a [`BindingStatus`].
+pub trait SubjectManager<S: BindingConditionType>: BindingConditionManager<S> {
+    /// Set the condition that the subject of the binding has been resolved
+    fn mark_subject_resolved(&mut self) {
+        self.manager().mark_true(S::subjectresolved());
+    }
+
+    /// Set the condition that the subject of the binding could not be resolved
+    fn mark_subject_not_resolved(&mut self, reason: &str, message: Option<String>) {
+        self.manager().mark_false(S::subjectresolved(), reason, message);
+    }
+}
+
+impl<S: BindingConditionType> SubjectManager<S> for BindingStatus<S> {}
+
+impl Binding {
+    /// Returns the status of the [`Binding`] for management, initializing it if absent.
+    pub fn status_mut(&mut self) -> &mut BindingStatus<BindingCondition> {
+        self.status.get_or_insert_with(BindingStatus::default)
+    }
+}
+
+impl HasStatus for Binding {
+    type Status = BindingStatus<BindingCondition>;
+
+    fn status(&self) -> Option<&Self::Status> {
+        self.status.as_ref()
+    }
+
+    fn status_mut(&mut self) -> &mut Self::Status {
+        Binding::status_mut(self)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, JsonSchema)]
@@ -24,7 +102,6 @@ pub struct BindingSpec {
 }
 
 // Found in knative.dev/pkg/tracker
-// TODO: implement From<Resource> for Reference
 /// Reference is modeled after corev1.ObjectReference, but omits fields
 /// unsupported by the tracker, and permits us to extend things in
 /// divergent ways.
@@ -41,8 +118,9 @@ pub struct Reference {
     pub subject: Subject
 }
 
+/// The subject of a [`Reference`], flattened into it as either a `name` or a `selector` field.
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "lowercase")]
 pub enum Subject {
     Name(String),
     Selector(LabelSelector)
@@ -54,6 +132,98 @@ impl Default for Subject {
     }
 }
 
+/// Format a [`LabelSelector`] as a `labelSelector` query for list requests to the API server.
+pub fn label_selector_query(selector: &LabelSelector) -> String {
+    let labels = selector.match_labels.iter()
+        .flatten()
+        .map(|(key, value)| format!("{key}={value}"));
+    let expressions = selector.match_expressions.iter()
+        .flatten()
+        .map(|expr| {
+            let values = expr.values.as_deref().unwrap_or_default().join(",");
+            match expr.operator.as_str() {
+                "In" => format!("{} in ({values})", expr.key),
+                "NotIn" => format!("{} notin ({values})", expr.key),
+                "DoesNotExist" => format!("!{}", expr.key),
+                _ => expr.key.clone(),
+            }
+        });
+    labels.chain(expressions).collect::<Vec<_>>().join(",")
+}
+
+/// Whether `labels` satisfy both the `matchLabels` and `matchExpressions` of `selector`. An empty
+/// selector matches everything.
+pub fn selector_matches(selector: &LabelSelector, labels: &BTreeMap<String, String>) -> bool {
+    let match_labels = selector.match_labels.iter()
+        .flatten()
+        .all(|(key, value)| labels.get(key) == Some(value));
+    let match_expressions = selector.match_expressions.iter()
+        .flatten()
+        .all(|expr| {
+            let values = expr.values.as_deref().unwrap_or_default();
+            let label = labels.get(&expr.key);
+            match expr.operator.as_str() {
+                "In" => matches!(label, Some(label) if values.contains(label)),
+                "NotIn" => !matches!(label, Some(label) if values.contains(label)),
+                "Exists" => label.is_some(),
+                "DoesNotExist" => label.is_none(),
+                _ => false,
+            }
+        });
+    match_labels && match_expressions
+}
+
+impl Reference {
+    /// Whether an object with `name` and `labels` is a subject of the reference: by name when the
+    /// subject is a [`Subject::Name`], otherwise by its selector.
+    pub fn matches(&self, name: &str, labels: &BTreeMap<String, String>) -> bool {
+        match &self.subject {
+            Subject::Name(subject) => subject == name,
+            Subject::Selector(selector) => selector_matches(selector, labels),
+        }
+    }
+
+    /// Fetch the subjects of the reference: the named object, or every object matching the
+    /// selector. The namespace defaults to `default_namespace` when the reference omits one.
+    pub async fn resolve(
+        &self,
+        client: kube::Client,
+        default_namespace: &str,
+    ) -> Result<Vec<DynamicObject>, Error> {
+        let (api_version, kind) = match (&self.api_version, &self.kind) {
+            (Some(api_version), Some(kind)) => (api_version, kind),
+            _ => Err(KRefErr::MalformedGVK)?,
+        };
+        let (group, version) = api_version.split_once('/').unwrap_or(("", api_version));
+        let gvk = GroupVersionKind::gvk(group, version, kind);
+
+        let ar = discover(&client, &gvk).await?;
+        let ns = self.namespace.as_deref().unwrap_or(default_namespace);
+        let api = Api::<DynamicObject>::namespaced_with(client, ns, &ar);
+
+        match &self.subject {
+            Subject::Name(name) => Ok(vec![api.get(name).await?]),
+            Subject::Selector(selector) => {
+                let params = ListParams::default().labels(&label_selector_query(selector));
+                Ok(api.list(&params).await?.items)
+            }
+        }
+    }
+}
+
+impl<T: kube::Resource<DynamicType = ()>> From<&T> for Reference {
+    /// A reference to `resource` by its name.
+    fn from(resource: &T) -> Reference {
+        let meta = resource.meta();
+        Reference {
+            kind: Some(T::kind(&()).into_owned()),
+            api_version: Some(T::api_version(&()).into_owned()),
+            namespace: meta.namespace.clone(),
+            subject: Subject::Name(meta.name.clone().unwrap_or_default()),
+        }
+    }
+}
+
 impl From<Reference> for ObjectReference {
     fn from(reference: Reference) -> ObjectReference {
         let Reference { api_version, kind, namespace, subject } = reference;
@@ -90,4 +260,220 @@ mod test {
         });
         assert_eq!(serde_json::to_string(&reference).unwrap(), serde_json::to_string(&json).unwrap())
     }
+
+    #[test]
+    fn reference_from_resource() {
+        use k8s_openapi::api::apps::v1::Deployment;
+
+        let deployment = Deployment {
+            metadata: ObjectMeta {
+                name: Some("my-deployment".into()),
+                namespace: Some("my-namespace".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let reference = Reference::from(&deployment);
+        assert_eq!(reference.kind.as_deref(), Some("Deployment"));
+        assert_eq!(reference.api_version.as_deref(), Some("apps/v1"));
+        assert_eq!(reference.namespace.as_deref(), Some("my-namespace"));
+        assert!(matches!(reference.subject, Subject::Name(name) if name == "my-deployment"));
+    }
+
+    fn labels(labels: &[(&str, &str)]) -> BTreeMap<String, String> {
+        labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn selected(selector: LabelSelector) -> Reference {
+        Reference { subject: Subject::Selector(selector), ..Default::default() }
+    }
+
+    #[test]
+    fn matches_labels() {
+        let reference = selected(LabelSelector {
+            match_labels: Some(labels(&[("app", "my-app"), ("tier", "web")])),
+            ..Default::default()
+        });
+        assert!(reference.matches("pod", &labels(&[("app", "my-app"), ("tier", "web"), ("extra", "x")])));
+        assert!(!reference.matches("pod", &labels(&[("app", "my-app")])));
+        assert!(!reference.matches("pod", &labels(&[("app", "other"), ("tier", "web")])));
+
+        // an empty selector matches everything
+        assert!(selected(LabelSelector::default()).matches("pod", &BTreeMap::new()));
+    }
+
+    #[test]
+    fn matches_expressions() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelectorRequirement;
+
+        let expression = |key: &str, operator: &str, values: &[&str]| LabelSelectorRequirement {
+            key: key.into(),
+            operator: operator.into(),
+            values: Some(values.iter().map(|v| v.to_string()).collect()),
+        };
+        let reference = selected(LabelSelector {
+            match_expressions: Some(vec![
+                expression("env", "In", &["prod", "staging"]),
+                expression("tier", "NotIn", &["db"]),
+            ]),
+            ..Default::default()
+        });
+        assert!(reference.matches("pod", &labels(&[("env", "prod")])));
+        assert!(reference.matches("pod", &labels(&[("env", "staging"), ("tier", "web")])));
+        assert!(!reference.matches("pod", &labels(&[("env", "dev")])));
+        assert!(!reference.matches("pod", &labels(&[("tier", "web")])));
+        assert!(!reference.matches("pod", &labels(&[("env", "prod"), ("tier", "db")])));
+
+        let reference = selected(LabelSelector {
+            match_expressions: Some(vec![
+                expression("app", "Exists", &[]),
+                expression("legacy", "DoesNotExist", &[]),
+            ]),
+            ..Default::default()
+        });
+        assert!(reference.matches("pod", &labels(&[("app", "any")])));
+        assert!(!reference.matches("pod", &labels(&[("app", "any"), ("legacy", "true")])));
+    }
+
+    #[test]
+    fn matches_by_name() {
+        let reference = Reference { subject: Subject::Name("my-pod".into()), ..Default::default() };
+        assert!(reference.matches("my-pod", &BTreeMap::new()));
+        assert!(!reference.matches("other-pod", &labels(&[("app", "my-app")])));
+    }
+
+    #[test]
+    fn subject_name_is_a_name_field() {
+        use serde_json::json;
+        let reference = Reference {
+            subject: Subject::Name("my-pod".into()),
+            ..Default::default()
+        };
+        assert_eq!(serde_json::to_value(&reference).unwrap(), json!({ "name": "my-pod" }));
+
+        let reference: Reference = serde_json::from_value(json!({ "name": "my-pod" })).unwrap();
+        assert!(matches!(reference.subject, Subject::Name(name) if name == "my-pod"));
+    }
+
+    #[test]
+    fn subject_selector_is_a_selector_field() {
+        use serde_json::json;
+        let selector = LabelSelector {
+            match_labels: Some([("app".to_string(), "my-app".to_string())].into()),
+            ..Default::default()
+        };
+        let reference = Reference {
+            kind: Some("Pod".into()),
+            api_version: Some("v1".into()),
+            namespace: Some("default".into()),
+            subject: Subject::Selector(selector.clone()),
+        };
+        let json = json!({
+            "kind": "Pod",
+            "apiVersion": "v1",
+            "namespace": "default",
+            "selector": { "matchLabels": { "app": "my-app" } }
+        });
+        assert_eq!(serde_json::to_value(&reference).unwrap(), json);
+
+        let reference: Reference = serde_json::from_value(json).unwrap();
+        assert!(matches!(reference.subject, Subject::Selector(s) if s == selector));
+    }
+
+    #[test]
+    fn can_manage_binding_subject() {
+        let mut binding: Binding = serde_json::from_value(serde_json::json!({
+            "kind": "SinkBinding",
+            "apiVersion": "sources.knative.dev/v1",
+            "spec": { "subject": { "apiVersion": "apps/v1", "kind": "Deployment", "name": "app" } },
+        })).unwrap();
+        assert!(binding.status.is_none());
+
+        let status = binding.status_mut();
+        assert!(!status.is_ready());
+
+        status.mark_subject_not_resolved("SubjectMissing", Some("deployment app not found".into()));
+        assert!(!status.is_ready());
+        assert_eq!(status.manager().get_top_level_condition().reason.as_deref(), Some("SubjectMissing"));
+
+        status.mark_subject_resolved();
+        assert!(status.is_ready());
+    }
+
+    #[test]
+    fn label_selector_formats_as_query() {
+        use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelectorRequirement;
+        let selector = LabelSelector {
+            match_labels: Some([("app".to_string(), "my-app".to_string())].into()),
+            match_expressions: Some(vec![
+                LabelSelectorRequirement {
+                    key: "tier".into(),
+                    operator: "In".into(),
+                    values: Some(vec!["web".into(), "api".into()]),
+                },
+                LabelSelectorRequirement {
+                    key: "canary".into(),
+                    operator: "DoesNotExist".into(),
+                    values: None,
+                },
+            ]),
+        };
+        assert_eq!(label_selector_query(&selector), "app=my-app,tier in (web,api),!canary");
+    }
+
+    fn pod(name: &str) -> DynamicObject {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "name": name, "namespace": "default", "labels": { "app": "my-app" } },
+        })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn resolves_named_subject() {
+        let (client, requests) = crate::mock::client(vec![pod("a"), pod("b")]);
+        let reference = Reference {
+            kind: Some("Pod".into()),
+            api_version: Some("v1".into()),
+            namespace: None,
+            subject: Subject::Name("b".into()),
+        };
+
+        let subjects = reference.resolve(client, "default").await.unwrap();
+        assert_eq!(subjects.len(), 1);
+        assert_eq!(subjects[0].metadata.name.as_deref(), Some("b"));
+        assert_eq!(requests.lock().unwrap().last().unwrap(), "/api/v1/namespaces/default/pods/b");
+    }
+
+    #[tokio::test]
+    async fn resolves_selected_subjects() {
+        let (client, requests) = crate::mock::client(vec![pod("a"), pod("b")]);
+        let reference = Reference {
+            kind: Some("Pod".into()),
+            api_version: Some("v1".into()),
+            namespace: Some("default".into()),
+            subject: Subject::Selector(LabelSelector {
+                match_labels: Some([("app".to_string(), "my-app".to_string())].into()),
+                ..Default::default()
+            }),
+        };
+
+        let subjects = reference.resolve(client, "other").await.unwrap();
+        let names = subjects.iter().filter_map(|s| s.metadata.name.as_deref()).collect::<Vec<_>>();
+        assert_eq!(names, ["a", "b"]);
+        let list = requests.lock().unwrap().last().unwrap().clone();
+        assert!(list.starts_with("/api/v1/namespaces/default/pods?"), "{list}");
+        assert!(list.contains("labelSelector=app%3Dmy-app"), "{list}");
+    }
+
+    #[tokio::test]
+    async fn subject_without_kind_is_malformed() {
+        let (client, _) = crate::mock::client(vec![]);
+        let reference = Reference {
+            subject: Subject::Name("a".into()),
+            ..Default::default()
+        };
+        let err = reference.resolve(client, "default").await.unwrap_err();
+        assert!(matches!(err, Error::KReferenceError(KRefErr::MalformedGVK)));
+    }
 }
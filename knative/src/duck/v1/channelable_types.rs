@@ -0,0 +1,127 @@
+use super::{
+    status_types::Status,
+    subscribable_types::{SubscribableStatus, SubscriberStatus},
+};
+use crate::derive::ConditionType;
+use knative_conditions::{ConditionAccessor, ConditionStatus, Conditions};
+use enumset::EnumSetType;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A baseline [`ConditionType`] for [`ChannelableStatus`].
+///
+/// Custom conditions should implement [`ChannelConditionType`] in order to be used by
+/// [`ChannelableStatus`].
+#[derive(ConditionType, EnumSetType, Deserialize, Serialize, Debug, JsonSchema)]
+pub enum ChannelCondition {
+    Ready,
+    /// Every subscriber of the channel is ready.
+    #[dependent]
+    SubscribersReady,
+}
+
+/// ChannelableStatus contains the Status of a Channelable object.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelableStatus<S: ChannelConditionType> {
+    /// inherits Status, which currently provides:
+    /// * ObservedGeneration - the 'Generation' of the Service that was last
+    ///   processed by the controller.
+    /// * Conditions - the latest available observations of a resource's current
+    ///   state.
+    #[serde(flatten)]
+    pub status: Status<S>,
+    /// Subscribers is populated with the statuses of each of the Channelable's subscribers.
+    #[serde(flatten)]
+    pub subscribable_status: SubscribableStatus,
+}
+
+impl<S: ChannelConditionType> ConditionAccessor<S> for ChannelableStatus<S> {
+    fn conditions(&mut self) -> &mut Conditions<S> {
+        self.status.conditions()
+    }
+}
+
+/// Provides management of `subscribers` on [`ChannelableStatus`].
+pub trait SubscribersManager<S: ChannelConditionType>: ChannelConditionManager<S> {
+    /// Return the [`ChannelableStatus`] of your CRD Status type.
+    fn channelable_status(&mut self) -> &mut ChannelableStatus<S>;
+
+    /// Set the subscriber statuses of the channel, marking the channel's subscribers ready only
+    /// if every subscriber is ready.
+    fn mark_subscribers(&mut self, subscribers: Vec<SubscriberStatus>) {
+        let unready = subscribers.iter()
+            .filter(|s| s.ready != ConditionStatus::True)
+            // Prioritize a False subscriber over an Unknown one
+            .reduce(|unready, s| if s.ready == ConditionStatus::False { s } else { unready })
+            .map(|s| (s.ready, format!(
+                "subscriber {} is not ready: {}",
+                s.uid.as_deref().unwrap_or("unknown"),
+                s.message.as_deref().unwrap_or("no message")
+            )));
+
+        match unready {
+            Some((ConditionStatus::False, message)) => {
+                self.manager().mark_false(S::subscribersready(), "SubscriberNotReady", Some(message))
+            }
+            Some((_, message)) => {
+                self.manager().mark_unknown(S::subscribersready(), "SubscriberNotReady", Some(message))
+            }
+            None => self.manager().mark_true(S::subscribersready()),
+        }
+
+        self.channelable_status().subscribable_status.subscribers = Some(subscribers);
+    }
+}
+
+impl<S: ChannelConditionType> SubscribersManager<S> for ChannelableStatus<S> {
+    fn channelable_status(&mut self) -> &mut ChannelableStatus<S> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn subscriber(uid: &str, ready: ConditionStatus) -> SubscriberStatus {
+        SubscriberStatus {
+            uid: Some(uid.into()),
+            ready,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unready_subscriber_makes_channel_unready() {
+        let mut status = ChannelableStatus::<ChannelCondition>::default();
+
+        status.mark_subscribers(vec![
+            subscriber("a", ConditionStatus::True),
+            subscriber("b", ConditionStatus::True),
+        ]);
+        assert!(status.is_ready());
+
+        status.mark_subscribers(vec![
+            subscriber("a", ConditionStatus::True),
+            SubscriberStatus {
+                message: Some("dispatch failed".into()),
+                ..subscriber("b", ConditionStatus::False)
+            },
+        ]);
+        assert!(!status.is_ready());
+        let manager = status.manager();
+        let ready = manager.get_top_level_condition();
+        assert!(ready.is_false());
+        assert_eq!(ready.reason.as_deref(), Some("SubscriberNotReady"));
+        assert_eq!(ready.message.as_deref(), Some("subscriber b is not ready: dispatch failed"));
+        assert_eq!(status.subscribable_status.subscribers.as_ref().map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn unknown_subscriber_makes_channel_unknown() {
+        let mut status = ChannelableStatus::<ChannelCondition>::default();
+        status.mark_subscribers(vec![subscriber("a", ConditionStatus::Unknown)]);
+        assert!(status.manager().get_top_level_condition().is_unknown());
+    }
+}
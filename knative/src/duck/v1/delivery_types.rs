@@ -0,0 +1,162 @@
+use super::source_types::Destination;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DeliveryErr {
+    #[error("{0} is not a valid ISO-8601 duration")]
+    InvalidDuration(String),
+}
+
+/// DeliverySpec contains the delivery options for event senders,
+/// such as channelable and source.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliverySpec {
+    /// DeadLetterSink is the sink receiving event that could not be sent to
+    /// a destination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dead_letter_sink: Option<Destination>,
+    /// Retry is the minimum number of retries the sender should attempt when
+    /// sending an event before moving it to the dead letter sink.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<i32>,
+    /// BackoffPolicy is the retry backoff policy (linear, exponential).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backoff_policy: Option<BackoffPolicyType>,
+    /// BackoffDelay is the delay before retrying.
+    /// More information on Duration format:
+    ///  - https://www.iso.org/iso-8601-date-and-time-format.html
+    ///  - https://en.wikipedia.org/wiki/ISO_8601
+    ///
+    /// For linear policy, backoff delay is backoffDelay*<numberOfRetries>.
+    /// For exponential policy, backoff delay is backoffDelay*2^<numberOfRetries>.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backoff_delay: Option<String>,
+    /// Timeout is the timeout of each single request. The value must be greater than 0.
+    /// More information on Duration format:
+    ///  - https://www.iso.org/iso-8601-date-and-time-format.html
+    ///  - https://en.wikipedia.org/wiki/ISO_8601
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+}
+
+/// BackoffPolicyType is the type for backoff policies
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, JsonSchema, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BackoffPolicyType {
+    /// Linear backoff policy
+    Linear,
+    /// Exponential backoff policy
+    Exponential,
+}
+
+impl DeliverySpec {
+    /// Returns the parsed `backoff_delay`, if set.
+    pub fn backoff_delay(&self) -> Option<Result<Duration, DeliveryErr>> {
+        self.backoff_delay.as_deref().map(parse_duration)
+    }
+
+    /// Returns the parsed `timeout`, if set.
+    pub fn timeout(&self) -> Option<Result<Duration, DeliveryErr>> {
+        self.timeout.as_deref().map(parse_duration)
+    }
+}
+
+/// Parse an ISO-8601 duration such as `PT0.2S` or `P1DT12H`.
+///
+/// Years and months are rejected since their length is ambiguous.
+pub fn parse_duration(s: &str) -> Result<Duration, DeliveryErr> {
+    let err = || DeliveryErr::InvalidDuration(s.to_string());
+
+    let rest = s.strip_prefix('P').ok_or_else(err)?;
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return Err(err()),
+        Some((date, time)) => (date, time),
+        None => (rest, ""),
+    };
+    if rest.is_empty() {
+        return Err(err())
+    }
+
+    let mut duration = Duration::ZERO;
+    let date_units: &[(char, u64)] = &[('W', 7 * 24 * 60 * 60), ('D', 24 * 60 * 60)];
+    let time_units: &[(char, u64)] = &[('H', 60 * 60), ('M', 60), ('S', 1)];
+
+    for (mut part, units) in [(date, date_units), (time, time_units)] {
+        // designators must appear in order, at most once
+        let mut units = units.iter();
+        while !part.is_empty() {
+            let idx = part.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(err)?;
+            let (number, tail) = part.split_at(idx);
+            let designator = tail.chars().next().ok_or_else(err)?;
+            let (_, seconds) = units.find(|(unit, _)| *unit == designator).ok_or_else(err)?;
+
+            let (whole, fraction) = number.split_once(['.', ',']).unwrap_or((number, ""));
+            if whole.is_empty()
+                || fraction.len() > 9
+                || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+            {
+                return Err(err())
+            }
+            let whole: u64 = whole.parse().map_err(|_| err())?;
+            let nanos: u64 = format!("{fraction:0<9}").parse().map_err(|_| err())?;
+
+            duration = whole.checked_mul(*seconds)
+                .map(Duration::from_secs)
+                .and_then(|d| duration.checked_add(d))
+                .and_then(|d| d.checked_add(Duration::from_nanos(nanos * seconds)))
+                .ok_or_else(err)?;
+
+            part = &tail[1..];
+        }
+    }
+
+    Ok(duration)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("PT0.2S"), Ok(Duration::from_millis(200)));
+        assert_eq!(parse_duration("PT1S"), Ok(Duration::from_secs(1)));
+        assert_eq!(parse_duration("PT1M30S"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("P1DT1H"), Ok(Duration::from_secs(25 * 60 * 60)));
+        assert_eq!(parse_duration("P1W"), Ok(Duration::from_secs(7 * 24 * 60 * 60)));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for invalid in ["", "P", "PT", "1S", "PT1", "PTS", "PT-1S", "P1S", "PT1S1M", "P1Y", "PT1.2.3S", "10s"] {
+            assert_eq!(
+                parse_duration(invalid),
+                Err(DeliveryErr::InvalidDuration(invalid.into())),
+                "{invalid} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn delivery_spec_durations() {
+        let delivery: DeliverySpec = serde_json::from_value(serde_json::json!({
+            "retry": 3,
+            "backoffPolicy": "exponential",
+            "backoffDelay": "PT0.2S",
+            "timeout": "not a duration"
+        })).unwrap();
+
+        assert_eq!(delivery.backoff_policy, Some(BackoffPolicyType::Exponential));
+        assert_eq!(delivery.backoff_delay(), Some(Ok(Duration::from_millis(200))));
+        assert!(matches!(delivery.timeout(), Some(Err(DeliveryErr::InvalidDuration(_)))));
+        // raw strings are kept for round trips
+        assert_eq!(delivery.backoff_delay.as_deref(), Some("PT0.2S"));
+        assert_eq!(serde_json::to_value(&delivery).unwrap()["timeout"], "not a duration");
+
+        assert!(DeliverySpec::default().backoff_delay().is_none());
+    }
+}
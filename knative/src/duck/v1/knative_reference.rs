@@ -3,18 +3,33 @@ use crate::error::Error;
 use thiserror::Error;
 use k8s_openapi::api::core::v1::ObjectReference;
 use kube::{
-    api::{DynamicObject, GroupVersionKind},
+    api::{ApiResource, DynamicObject, GroupVersionKind},
     discovery, Api,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Serialize, Deserialize, Error, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Error, Clone, PartialEq)]
 pub enum KRefErr {
     #[error("apiVersion is incomplete or group does not exist")]
     MalformedGVK,
     #[error("must be namespaced")]
     MustBeNamespaced,
+    #[error("{0} must not be empty")]
+    EmptyField(String),
+}
+
+/// Errors discovering the API resource of a [`KReference`].
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum DiscoveryErr {
+    #[error("no resource for {group}/{version} {kind}")]
+    NotFound {
+        group: String,
+        version: String,
+        kind: String,
+    },
 }
 
 /// KReference contains enough information to refer to another object.
@@ -55,47 +70,392 @@ impl From<KReference> for ObjectReference {
     }
 }
 
+impl TryFrom<ObjectReference> for KReference {
+    type Error = KRefErr;
+
+    /// Convert an [`ObjectReference`], which must have a kind and name. The api version is kept in
+    /// its `group/version` form.
+    fn try_from(reference: ObjectReference) -> Result<KReference, KRefErr> {
+        Ok(KReference {
+            kind: reference.kind.ok_or(KRefErr::MalformedGVK)?,
+            namespace: reference.namespace,
+            name: reference.name.ok_or(KRefErr::MalformedGVK)?,
+            api_version: reference.api_version,
+            group: None,
+        })
+    }
+}
+
 impl KReference {
+    /// Validate that the name and kind of the reference are set and that its api version or group
+    /// is well formed, before any request is made to resolve it.
+    pub fn validate(&self) -> Result<(), KRefErr> {
+        if self.name.trim().is_empty() {
+            return Err(KRefErr::EmptyField("name".into()))
+        }
+        if self.kind.trim().is_empty() {
+            return Err(KRefErr::EmptyField("kind".into()))
+        }
+        match self.group_version()? {
+            ("", None) | (_, Some("")) => Err(KRefErr::MalformedGVK),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fetch the referent, which must be namespaced. Reconcilers that need more than the address
+    /// of the referent, such as its labels or spec, can read them from the returned object.
+    pub async fn resolve(
+        &self,
+        client: kube::Client,
+    ) -> Result<DynamicObject, Error> {
+        let ns = self.namespace.as_ref()
+            .ok_or(KRefErr::MustBeNamespaced)?;
+
+        self.resolve_in(client, ns).await
+    }
+
+    /// Fetch the referent, defaulting to `namespace` when the reference omits one.
+    pub async fn resolve_in(
+        &self,
+        client: kube::Client,
+        namespace: &str,
+    ) -> Result<DynamicObject, Error> {
+        self.validate()?;
+        let ns = self.namespace.as_deref().unwrap_or(namespace);
+        let ar = self.api_resource(&client).await?;
+        let api = Api::<DynamicObject>::namespaced_with(client, ns, &ar);
+        Ok(api.get(&self.name).await?)
+    }
+
+    /// Resolve the uri of the referent, which must be namespaced.
     pub async fn resolve_uri(
         &self,
         client: kube::Client,
     ) -> Result<url::Url, Error> {
-        let KReference {
-            group,
-            api_version,
-            namespace,
-            kind,
-            name,
-            ..
-        } = self;
-
-        let ns = namespace.as_ref()
-            .ok_or(KRefErr::MustBeNamespaced)?;
+        Ok(self.resolve(client).await?.address().await?)
+    }
 
-        let (group, api_version) = match (group, api_version) {
+    /// Resolve the uri of the referent, defaulting to `namespace` when the reference omits one.
+    pub async fn resolve_uri_in(
+        &self,
+        client: kube::Client,
+        namespace: &str,
+    ) -> Result<url::Url, Error> {
+        Ok(self.resolve_in(client, namespace).await?.address().await?)
+    }
+
+    /// The group and version of the referent. The version is `None` when it is to be discovered.
+    fn group_version(&self) -> Result<(&str, Option<&str>), KRefErr> {
+        match (&self.group, &self.api_version) {
             (Some(group), Some(api_version)) => {
-                (group.as_str(), api_version.as_str())
+                Ok((group.as_str(), Some(api_version.as_str())))
             }
-            (None, Some(api_version)) if api_version.contains('/') => {
-                let mut iter = api_version.split('/');
-                (iter.next().unwrap(), iter.next().unwrap())
+            (None, Some(api_version)) => match api_version.split_once('/') {
+                Some(("", _)) => Err(KRefErr::MalformedGVK),
+                Some((group, version)) => Ok((group, Some(version))),
+                // the core group is unnamed, as in `v1`
+                None => Ok(("", Some(api_version.as_str()))),
             },
-            _ => Err(KRefErr::MalformedGVK)?
+            // the version is discovered
+            (Some(group), None) => Ok((group.as_str(), None)),
+            _ => Err(KRefErr::MalformedGVK)
+        }
+    }
+
+    /// Discover the API resource of the referent.
+    async fn api_resource(&self, client: &kube::Client) -> Result<ApiResource, Error> {
+        match self.group_version()? {
+            (group, Some(version)) => discover(client, &GroupVersionKind::gvk(group, version, &self.kind)).await,
+            (group, None) => discover_group(client, group, &self.kind).await,
+        }
+    }
+}
+
+/// The group, version and kind of a [`KReference`]. The version is `None` when it is discovered.
+type ApiResourceKey = (String, Option<String>, String);
+
+/// Resolves [`KReference`]s, caching the discovered API resource of each kind so that resolving
+/// many references makes a single discovery request per kind. Clones share the cache.
+#[derive(Clone)]
+pub struct KReferenceResolver {
+    client: kube::Client,
+    cache: Arc<Mutex<HashMap<ApiResourceKey, ApiResource>>>,
+}
+
+impl KReferenceResolver {
+    pub fn new(client: kube::Client) -> Self {
+        KReferenceResolver {
+            client,
+            cache: Arc::default(),
+        }
+    }
+
+    /// See [`KReference::resolve_uri`].
+    pub async fn resolve_uri(&self, reference: &KReference) -> Result<url::Url, Error> {
+        let ns = reference.namespace.as_ref()
+            .ok_or(KRefErr::MustBeNamespaced)?;
+
+        self.resolve_uri_in(reference, ns).await
+    }
+
+    /// See [`KReference::resolve_uri_in`].
+    pub async fn resolve_uri_in(&self, reference: &KReference, namespace: &str) -> Result<url::Url, Error> {
+        let obj = self.resolve_in(reference, namespace).await?;
+        Ok(obj.address().await?)
+    }
+
+    /// See [`KReference::resolve_in`].
+    ///
+    /// Discovery errors are not cached, so a kind that is not yet served is discovered again on
+    /// the next resolution. A cached API resource that the API server no longer serves, such as a
+    /// removed CRD version, is evicted and discovered again when a get of the referent is not
+    /// found.
+    pub async fn resolve_in(&self, reference: &KReference, namespace: &str) -> Result<DynamicObject, Error> {
+        reference.validate()?;
+        let (group, version) = reference.group_version()?;
+        let key = (group.to_string(), version.map(str::to_string), reference.kind.clone());
+        let ns = reference.namespace.as_deref().unwrap_or(namespace);
+
+        let cached = self.cache.lock().unwrap().get(&key).cloned();
+        if let Some(ar) = cached {
+            let api = Api::<DynamicObject>::namespaced_with(self.client.clone(), ns, &ar);
+            match api.get(&reference.name).await {
+                Err(kube::Error::Api(ref res)) if res.code == 404 => {
+                    self.cache.lock().unwrap().remove(&key);
+                }
+                Err(kube::Error::Discovery(_)) => {
+                    self.cache.lock().unwrap().remove(&key);
+                }
+                res => return Ok(res?),
+            }
+        }
+
+        let ar = reference.api_resource(&self.client).await?;
+        self.cache.lock().unwrap().insert(key, ar.clone());
+        let api = Api::<DynamicObject>::namespaced_with(self.client.clone(), ns, &ar);
+        Ok(api.get(&reference.name).await?)
+    }
+}
+
+/// Discover the API resource of `gvk`, naming the gvk if the API server does not serve it.
+pub(crate) async fn discover(client: &kube::Client, gvk: &GroupVersionKind) -> Result<ApiResource, Error> {
+    let (ar, _caps) = discovery::pinned_kind(client, gvk).await
+        .map_err(|e| match e {
+            kube::Error::Api(ref res) if res.code == 404 => not_found(gvk),
+            kube::Error::Discovery(_) => not_found(gvk),
+            e => Error::from(e),
+        })?;
+    Ok(ar)
+}
+
+/// Discover the API resource of `kind` in the preferred version of `group`, following Knative's
+/// ResolveGroup for references that omit their api version.
+pub(crate) async fn discover_group(client: &kube::Client, group: &str, kind: &str) -> Result<ApiResource, Error> {
+    let gvk = GroupVersionKind::gvk(group, "", kind);
+    let api_group = discovery::group(client, group).await
+        .map_err(|e| match e {
+            kube::Error::Api(ref res) if res.code == 404 => not_found(&gvk),
+            kube::Error::Discovery(_) => not_found(&gvk),
+            e => Error::from(e),
+        })?;
+    let (ar, _caps) = api_group.recommended_kind(kind)
+        .ok_or_else(|| not_found(&gvk))?;
+    Ok(ar)
+}
+
+fn not_found(gvk: &GroupVersionKind) -> Error {
+    DiscoveryErr::NotFound {
+        group: gvk.group.clone(),
+        version: gvk.version.clone(),
+        kind: gvk.kind.clone(),
+    }.into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::{self, read_mock};
+
+    fn reference(api_version: &str, kind: &str) -> KReference {
+        KReference {
+            kind: kind.into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some(api_version.into()),
+            group: None,
+        }
+    }
+
+    #[test]
+    fn validates_fields() {
+        assert_eq!(reference("eventing.knative.dev/v1", "Broker").validate(), Ok(()));
+
+        let mut nameless = reference("eventing.knative.dev/v1", "Broker");
+        nameless.name = "".into();
+        assert_eq!(nameless.validate(), Err(KRefErr::EmptyField("name".into())));
+
+        assert_eq!(reference("eventing.knative.dev/v1", " ").validate(), Err(KRefErr::EmptyField("kind".into())));
+        assert_eq!(KRefErr::EmptyField("kind".into()).to_string(), "kind must not be empty");
+
+        assert_eq!(reference("v1", "Service").validate(), Ok(()));
+        assert_eq!(reference("", "Service").validate(), Err(KRefErr::MalformedGVK));
+        assert_eq!(reference("/v1", "Broker").validate(), Err(KRefErr::MalformedGVK));
+        assert_eq!(reference("eventing.knative.dev/", "Broker").validate(), Err(KRefErr::MalformedGVK));
+
+        let mut groupless = reference("eventing.knative.dev/v1", "Broker");
+        groupless.api_version = None;
+        assert_eq!(groupless.validate(), Err(KRefErr::MalformedGVK));
+        groupless.group = Some("eventing.knative.dev".into());
+        assert_eq!(groupless.validate(), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn invalid_reference_makes_no_requests() {
+        let (client, requests) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let mut nameless = reference("eventing.knative.dev/v1", "Broker");
+        nameless.name = "".into();
+
+        let err = nameless.resolve_uri(client).await.unwrap_err();
+        assert!(matches!(err, Error::KReferenceError(KRefErr::EmptyField(field)) if field == "name"));
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn round_trips_object_reference() {
+        let reference = reference("eventing.knative.dev/v1", "Broker");
+        let object_reference = ObjectReference::from(reference.clone());
+        let round_tripped = KReference::try_from(object_reference.clone()).unwrap();
+        assert_eq!(round_tripped.kind, reference.kind);
+        assert_eq!(round_tripped.namespace, reference.namespace);
+        assert_eq!(round_tripped.name, reference.name);
+        assert_eq!(round_tripped.api_version, reference.api_version);
+
+        let nameless = ObjectReference { name: None, ..object_reference.clone() };
+        assert_eq!(KReference::try_from(nameless).unwrap_err(), KRefErr::MalformedGVK);
+        let kindless = ObjectReference { kind: None, ..object_reference };
+        assert_eq!(KReference::try_from(kindless).unwrap_err(), KRefErr::MalformedGVK);
+    }
+
+    #[tokio::test]
+    async fn discovery_miss_names_the_gvk() {
+        let (client, _) = mock::client(vec![read_mock("default_broker.yaml")]);
+
+        let err = reference("apps.example.dev/v1", "Widget").resolve_uri(client.clone()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DiscoveryError(DiscoveryErr::NotFound { ref group, ref version, ref kind })
+                if group == "apps.example.dev" && version == "v1" && kind == "Widget"
+        ));
+        assert_eq!(err.to_string(), "Error discovery: no resource for apps.example.dev/v1 Widget");
+
+        // the group exists, but not the kind
+        let err = reference("eventing.knative.dev/v1", "Trigger").resolve_uri(client).await.unwrap_err();
+        assert!(matches!(err, Error::DiscoveryError(DiscoveryErr::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn resolves_object() {
+        let (client, requests) = mock::client(vec![read_mock("default_broker.yaml")]);
+
+        let broker = reference("eventing.knative.dev/v1", "Broker").resolve(client).await.unwrap();
+        assert_eq!(broker.metadata.name.as_deref(), Some("default"));
+        assert_eq!(broker.metadata.namespace.as_deref(), Some("default"));
+        // the address is read from the object without another request
+        let uri = broker.address().await.unwrap();
+        assert_eq!(uri.as_str(), "http://broker-ingress.default.svc.cluster.local/default/default");
+        // discovery, then a single get
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn resolves_core_group_reference() {
+        let (client, requests) = mock::client(vec![read_mock("default_service.yaml")]);
+
+        let uri = reference("v1", "Service").resolve_uri(client).await.unwrap();
+        assert_eq!(uri.as_str(), "http://default.default.svc.cluster.local/");
+        assert_eq!(requests.lock().unwrap()[0], "/api/v1");
+    }
+
+    #[tokio::test]
+    async fn resolver_caches_discovery() {
+        let (client, requests) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let resolver = KReferenceResolver::new(client);
+        let broker = reference("eventing.knative.dev/v1", "Broker");
+
+        let first = resolver.resolve_uri(&broker).await.unwrap();
+        let second = resolver.clone().resolve_uri(&broker).await.unwrap();
+        assert_eq!(first, second);
+
+        let requests = requests.lock().unwrap();
+        let discovery = requests.iter().filter(|r| r.as_str() == "/apis/eventing.knative.dev/v1").count();
+        assert_eq!(discovery, 1, "{requests:?}");
+        assert_eq!(requests.len(), 3, "a single discovery and two gets");
+    }
+
+    #[tokio::test]
+    async fn resolver_rediscovers_resources_of_missing_objects() {
+        let (client, requests) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let resolver = KReferenceResolver::new(client);
+        let mut missing = reference("eventing.knative.dev/v1", "Broker");
+        missing.name = "missing".into();
+
+        assert!(resolver.resolve_uri(&missing).await.is_err());
+        assert!(resolver.resolve_uri(&missing).await.is_err());
+        // the cached resource is evicted and discovered again when the object is not found
+        let discovery = requests.lock().unwrap().iter()
+            .filter(|r| r.as_str() == "/apis/eventing.knative.dev/v1")
+            .count();
+        assert_eq!(discovery, 2);
+        assert_eq!(resolver.cache.lock().unwrap().len(), 1);
+
+        // discovery errors are not cached
+        let err = resolver.resolve_uri(&reference("apps.example.dev/v1", "Widget")).await.unwrap_err();
+        assert!(matches!(err, Error::DiscoveryError(DiscoveryErr::NotFound { .. })));
+        assert_eq!(resolver.cache.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn resolver_evicts_resources_that_are_no_longer_served() {
+        let (client, _) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let resolver = KReferenceResolver::new(client);
+        let broker = KReference {
+            api_version: None,
+            group: Some("eventing.knative.dev".into()),
+            ..reference("eventing.knative.dev/v1", "Broker")
         };
+        // a version of the CRD that has since been removed
+        let key = ("eventing.knative.dev".to_string(), None, "Broker".to_string());
+        let removed = ApiResource::from_gvk(&GroupVersionKind::gvk("eventing.knative.dev", "v1beta1", "Broker"));
+        resolver.cache.lock().unwrap().insert(key.clone(), removed);
 
-        let gvk = GroupVersionKind::gvk(
-            group,
-            api_version,
-            kind,
-        );
+        let uri = resolver.resolve_uri(&broker).await.expect("rediscovers the served version");
+        assert_eq!(uri.as_str(), "http://broker-ingress.default.svc.cluster.local/default/default");
+        assert_eq!(resolver.cache.lock().unwrap()[&key].version, "v1");
+    }
+
+    #[tokio::test]
+    async fn resolves_group_to_preferred_version() {
+        let (client, _) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let broker = |kind: &str| KReference {
+            kind: kind.into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: None,
+            group: Some("eventing.knative.dev".into()),
+        };
 
-        let (ar, _caps) = discovery::pinned_kind(&client, &gvk).await?;
-        let api = Api::<DynamicObject>::namespaced_with(client.clone(), ns, &ar);
-        let obj = api.get(name).await?;
-        let url = obj.address().await?;
+        let uri = broker("Broker").resolve_uri(client.clone()).await.expect("group resolves to v1");
+        assert_eq!(uri.as_str(), "http://broker-ingress.default.svc.cluster.local/default/default");
 
-        debug_assert!(!url.cannot_be_a_base());
+        let err = broker("Trigger").resolve_uri(client.clone()).await.unwrap_err();
+        assert!(matches!(err, Error::DiscoveryError(DiscoveryErr::NotFound { .. })));
 
-        Ok(url)
+        let mut missing = broker("Widget");
+        missing.group = Some("apps.example.dev".into());
+        let err = missing.resolve_uri(client).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DiscoveryError(DiscoveryErr::NotFound { ref group, .. }) if group == "apps.example.dev"
+        ));
     }
 }
@@ -1,5 +1,8 @@
 pub mod binding_types;
+pub mod channelable_types;
+pub mod delivery_types;
 pub mod source_types;
 pub mod status_types;
+pub mod subscribable_types;
 pub mod addressable_type;
 pub mod knative_reference;
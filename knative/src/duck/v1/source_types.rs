@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 use super::{
-    knative_reference::KReference,
+    addressable_type::{normalize_url, Addressable, AddressableErr, AddressableTypeExt},
+    knative_reference::{KRefErr, KReference},
     status_types::Status,
 };
 use crate::derive::ConditionType;
@@ -10,6 +11,7 @@ use enumset::EnumSetType;
 use thiserror::Error;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
@@ -25,16 +27,34 @@ impl SourceSpec {
     pub fn ce_overrides(&self) -> Option<CloudEventOverrides> {
         self.ce_overrides.clone()
     }
+
+    /// Validate that the sink is present and well-formed. A missing sink is
+    /// [`DestinationErr::Empty`].
+    pub fn validate(&self) -> Result<(), DestinationErr> {
+        self.sink.as_ref().ok_or(DestinationErr::Empty)?.validate()
+    }
 }
 
-#[derive(Error, Debug, Clone, Copy)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum DestinationErr {
     #[error("destination missing Ref and URI, expected at least one")]
     Empty,
+    #[error("destination has both Ref and an absolute URI, expected URI to be relative to Ref")]
+    AbsoluteUriWithRef,
+    #[error("destination has a relative URI without a Ref, expected an absolute URI")]
+    RelativeUriWithoutRef,
+    #[error("destination has an absolute URI without a scheme or host, expected both")]
+    InvalidAbsoluteUri,
+    #[error("destination Ref could not be resolved: {0}")]
+    RefResolution(KRefErr),
+    #[error("destination URI could not be parsed: {0}")]
+    UriParse(url::ParseError),
+    #[error("destination URI is not a valid http URI: {0}")]
+    HttpUriParse(String),
 }
 
 /// Destination represents a target of an invocation over HTTP.
-#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[derive(Clone, Debug, Default, JsonSchema)]
 pub struct Destination {
     /// Ref points to an Addressable.
     #[serde(rename = "ref")]
@@ -43,86 +63,344 @@ pub struct Destination {
     /// Relative URIs will be resolved using the base URI retrieved from Ref.
     // url::Url schemars definition denotes the "uri" json schema type
     #[schemars(with = "Option<url::Url>")]
-    #[serde(default, with = "uri_serde")]
+    #[serde(default)]
     pub uri: Option<http::Uri>,
+    /// The fragment of `uri`, which [`http::Uri`] discards.
+    #[serde(skip)]
+    fragment: Option<String>,
+    /// CACerts are the Certification Authority (CA) certificates in PEM format that the source
+    /// trusts when sending events to the destination.
+    #[serde(default, rename = "CACerts", skip_serializing_if = "Option::is_none")]
+    pub ca_certs: Option<String>,
+    /// Audience is the OIDC audience of the destination.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
 }
 
-/// A version of [`http_serde::uri`](https://gitlab.com/kornelski/http-serde) with Option support
-/// following [serde #1301](https://github.com/serde-rs/serde/issues/1301).
-mod uri_serde {
-    use http::Uri;
-    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+/// The serialized form of a [`Destination`], with `uri` kept as a string so that its fragment
+/// survives a round trip.
+#[derive(Deserialize, Serialize)]
+struct RawDestination {
+    #[serde(rename = "ref")]
+    ref_: Option<KReference>,
+    #[serde(default)]
+    uri: Option<String>,
+    #[serde(default, rename = "CACerts", skip_serializing_if = "Option::is_none")]
+    ca_certs: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    audience: Option<String>,
+}
 
-    pub fn serialize<S: Serializer>(uri: &Option<Uri>, ser: S) -> Result<S::Ok, S::Error> {
-        #[derive(Serialize)]
-        struct Helper<'a>(#[serde(with = "http_serde::uri")] &'a Uri);
-        uri.as_ref().map(Helper).serialize(ser)
+impl<'de> Deserialize<'de> for Destination {
+    fn deserialize<D: serde::Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let raw = RawDestination::deserialize(de)?;
+        let (uri, fragment) = match &raw.uri {
+            Some(uri) => {
+                let (uri, fragment) = match uri.split_once('#') {
+                    Some((uri, fragment)) => (uri, Some(fragment.to_string())),
+                    None => (uri.as_str(), None),
+                };
+                (Some(uri.parse::<http::Uri>().map_err(serde::de::Error::custom)?), fragment)
+            }
+            None => (None, None),
+        };
+        Ok(Destination {
+            ref_: raw.ref_,
+            uri,
+            fragment,
+            ca_certs: raw.ca_certs,
+            audience: raw.audience,
+        })
     }
+}
 
-    pub fn deserialize<'de, D>(de: D) -> Result<Option<Uri>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        #[derive(Deserialize)]
-        struct Helper(#[serde(with = "http_serde::uri")] Uri);
-        let helper = Option::deserialize(de)?;
-        Ok(helper.map(|Helper(uri)| uri))
+impl Serialize for Destination {
+    fn serialize<S: serde::Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let uri = self.uri.as_ref().map(|uri| match &self.fragment {
+            Some(fragment) => format!("{uri}#{fragment}"),
+            None => uri.to_string(),
+        });
+        RawDestination {
+            ref_: self.ref_.clone(),
+            uri,
+            ca_certs: self.ca_certs.clone(),
+            audience: self.audience.clone(),
+        }.serialize(ser)
     }
 }
 
 impl From<KReference> for Destination {
+    /// See [`Destination::from_ref`].
     fn from(reference: KReference) -> Self {
+        Destination::from_ref(reference)
+    }
+}
+
+impl TryFrom<url::Url> for Destination {
+    type Error = DestinationErr;
+
+    /// See [`Destination::from_uri`].
+    fn try_from(url: url::Url) -> Result<Self, DestinationErr> {
+        Destination::from_uri(url)
+    }
+}
+
+impl Destination {
+    /// A destination that resolves to the uri of the referenced Addressable.
+    ///
+    /// The group of the reference is folded into its api version. A reference with only a group
+    /// keeps it, so that its version is discovered when resolved.
+    pub fn from_ref(reference: KReference) -> Self {
+        // combine the group and api_version, handling the case that this was done already
+        let (api_version, group) = match (reference.api_version, reference.group) {
+            (Some(api_version), _) if api_version.contains('/') => (Some(api_version), None),
+            (Some(api_version), Some(group)) => (Some(group + "/" + &api_version), None),
+            (Some(api_version), None) => (Some(api_version), None),
+            (None, group) => (None, group),
+        };
         Destination {
             ref_: Some(KReference {
-                // combine the group and api_version, handling the case that this was done already
-                api_version: match (reference.api_version, reference.group) {
-                    (Some(api_version), _) if api_version.contains('/') => Some(api_version),
-                    (Some(api_version), Some(group)) => Some(group + "/" + &api_version),
-                    (Some(api_version), None) => Some(api_version),
-                    (None, _) => None,
-                },
-                group: None,
+                api_version,
+                group,
                 kind: reference.kind,
                 namespace: reference.namespace,
                 name: reference.name,
             }),
             uri: None,
+            fragment: None,
+            ca_certs: None,
+            audience: None,
         }
     }
-}
 
-impl From<url::Url> for Destination {
-    fn from(url: url::Url) -> Self {
-        Destination {
+    /// A destination that is an absolute URL.
+    ///
+    /// A URL can not be expressed as a [`KReference`], which always refers to an object. To
+    /// resolve a path relative to a referenced object, set `uri` on a destination constructed with
+    /// [`Destination::from_ref`].
+    ///
+    /// Fails for URLs that are not valid http URIs, such as `data:` URLs that cannot be a base or
+    /// URLs longer than an http URI allows.
+    pub fn from_uri(mut url: url::Url) -> Result<Self, DestinationErr> {
+        let fragment = url.fragment().map(str::to_string);
+        url.set_fragment(None);
+        let uri = url.as_str().parse::<http::Uri>()
+            .map_err(|e| DestinationErr::HttpUriParse(e.to_string()))?;
+        Ok(Destination {
             ref_: None,
-            uri: Some(url.as_str().parse::<http::Uri>().unwrap()),
+            uri: Some(uri),
+            fragment,
+            ca_certs: None,
+            audience: None,
+        })
+    }
+
+    /// Validate that the destination is exactly one of a ref or an absolute uri, or a ref with a
+    /// relative uri.
+    pub fn validate(&self) -> Result<(), DestinationErr> {
+        match (&self.ref_, &self.uri) {
+            (None, None) => Err(DestinationErr::Empty),
+            (Some(_), Some(uri)) if is_absolute(uri) => Err(DestinationErr::AbsoluteUriWithRef),
+            (None, Some(uri)) => validate_absolute(uri),
+            _ => Ok(()),
         }
     }
-}
 
-impl Destination {
+    /// Resolve the uri of the destination, awaiting the resolution of its [`KReference`] when set.
     pub async fn resolve_uri(
         &self,
         client: kube::Client,
     ) -> Result<url::Url, Error> {
+        self.resolve(client, None).await
+    }
+
+    /// Resolve the destination to an [`Addressable`], carrying the CA certificates and audience
+    /// used to send to it alongside its uri so that senders can configure TLS and OIDC.
+    ///
+    /// As with Knative's resolver, those of the referent's address are used unless the
+    /// destination sets its own.
+    pub async fn resolve_addressable(
+        &self,
+        client: kube::Client,
+    ) -> Result<Addressable, Error> {
+        self.addressable(client, None).await
+    }
+
+    /// Resolve the destination to an [`Addressable`], defaulting the namespace of the
+    /// [`KReference`] to the namespace of the `holder` object that embeds this destination.
+    ///
+    /// See [`Destination::resolve_addressable`].
+    pub async fn resolve_addressable_for(
+        &self,
+        client: kube::Client,
+        holder: &impl kube::Resource,
+    ) -> Result<Addressable, Error> {
+        self.addressable(client, holder.meta().namespace.as_deref()).await
+    }
+
+    async fn addressable(
+        &self,
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> Result<Addressable, Error> {
+        let mut referent = None;
+        let url = {
+            let referent = &mut referent;
+            self.resolve_uri_with(|ref_| async move {
+                let obj = match namespace {
+                    Some(ns) => ref_.resolve_in(client, ns).await?,
+                    None => ref_.resolve(client).await?,
+                };
+                let address = obj.addressable().await?;
+                let url = address.url.clone()
+                    .ok_or_else(|| AddressableErr::UrlNotSet(ref_.name.clone()))?;
+                *referent = Some(address);
+                Ok(url)
+            }).await?
+        };
+        let referent = referent.unwrap_or_default();
+        Ok(Addressable {
+            name: referent.name,
+            url: Some(url),
+            ca_certs: self.ca_certs.clone().or(referent.ca_certs),
+            audience: self.audience.clone().or(referent.audience),
+        })
+    }
+
+    /// Resolve the uri of the destination, defaulting the namespace of the [`KReference`] to the
+    /// namespace of the `holder` object that embeds this destination.
+    pub async fn resolve_uri_for(
+        &self,
+        client: kube::Client,
+        holder: &impl kube::Resource,
+    ) -> Result<url::Url, Error> {
+        self.resolve(client, holder.meta().namespace.as_deref()).await
+    }
+
+    async fn resolve(
+        &self,
+        client: kube::Client,
+        namespace: Option<&str>,
+    ) -> Result<url::Url, Error> {
+        self.resolve_uri_with(|ref_| async move {
+            match namespace {
+                Some(ns) => ref_.resolve_uri_in(client, ns).await,
+                None => ref_.resolve_uri(client).await,
+            }
+        }).await
+    }
+
+    /// Resolve the uri of the destination, memoizing the uri of the [`KReference`] in `cache` so
+    /// that resolving the same reference again does not query the API server.
+    pub async fn resolve_uri_cached(
+        &self,
+        client: kube::Client,
+        cache: &mut ResolutionCache,
+    ) -> Result<url::Url, Error> {
+        self.resolve_uri_with(|ref_| async move {
+            let key = ResolutionCache::key(ref_);
+            if let Some(url) = cache.0.get(&key) {
+                return Ok(url.clone())
+            }
+            let url = ref_.resolve_uri(client).await?;
+            cache.0.insert(key, url.clone());
+            Ok(url)
+        }).await
+    }
+
+    /// Resolve the uri of the destination, using `lookup` to resolve the uri of the
+    /// [`KReference`]. This allows destinations to be resolved without a [`kube::Client`], such as
+    /// in tests or by alternative backends.
+    ///
+    /// A relative `uri` is resolved against the uri of the ref. An absolute `uri` takes precedence
+    /// over the ref, which is then not resolved, even though such a destination does not pass
+    /// [`validate`](Destination::validate).
+    ///
+    /// The query and fragment of `uri` are preserved, replacing any query or fragment of the ref's
+    /// uri.
+    pub async fn resolve_uri_with<'a, F, Fut>(&'a self, lookup: F) -> Result<url::Url, Error>
+    where
+        F: FnOnce(&'a KReference) -> Fut,
+        Fut: Future<Output = Result<url::Url, Error>>,
+    {
         match (&self.ref_, &self.uri) {
+            (Some(_), Some(uri)) if is_absolute(uri) => {
+                let mut url = url::Url::parse(uri.to_string().as_str())
+                    .map_err(DestinationErr::UriParse)?;
+                url.set_fragment(self.fragment.as_deref());
+                Ok(normalize_url(url))
+            }
             (Some(ref ref_), uri) => {
-                let mut url = ref_.resolve_uri(client).await?;
+                let mut url = lookup(ref_).await.map_err(|e| match e {
+                    Error::KReferenceError(e) => DestinationErr::RefResolution(e).into(),
+                    e => e,
+                })?;
                 // If both ref and uri are specified, uri is relative to ref.
                 // https://github.com/knative/specs/blob/main/specs/eventing/control-plane.md#destination-resolution
                 if let Some(uri) = uri {
-                    url.path_segments_mut()
-                        .expect("KReference url must be base")
-                        .push(uri.path());
+                    // Unlike `Url::join`, the path of the uri is appended to the path of the ref
+                    // rather than replacing it. A uri of only a query keeps the path of the ref.
+                    let relative_path = uri.path().trim_start_matches('/');
+                    if !relative_path.is_empty() {
+                        let path = format!("{}/{}", url.path().trim_end_matches('/'), relative_path);
+                        url.set_path(&path);
+                    }
+                    if let Some(query) = uri.query() {
+                        url.set_query(Some(query));
+                    }
+                    if let Some(fragment) = &self.fragment {
+                        url.set_fragment(Some(fragment));
+                    }
                 }
-                Ok(url)
+                Ok(normalize_url(url))
+            }
+            (None, Some(uri)) => {
+                validate_absolute(uri)?;
+                let mut url = url::Url::parse(uri.to_string().as_str())
+                    .map_err(DestinationErr::UriParse)?;
+                url.set_fragment(self.fragment.as_deref());
+                Ok(normalize_url(url))
             }
-            (None, Some(uri)) => Ok(url::Url::parse(uri.to_string().as_str())?),
             (None, None) => Err(DestinationErr::Empty)?,
         }
     }
 }
 
+fn is_absolute(uri: &http::Uri) -> bool {
+    uri.scheme().is_some() && uri.host().is_some()
+}
+
+/// A uri without a ref must be absolute, with both a scheme and a host. A uri with only one of
+/// the two, such as `sink.default.svc:8080`, would otherwise resolve to garbage.
+fn validate_absolute(uri: &http::Uri) -> Result<(), DestinationErr> {
+    match (uri.scheme(), uri.host()) {
+        (Some(_), Some(_)) => Ok(()),
+        (None, None) => Err(DestinationErr::RelativeUriWithoutRef),
+        _ => Err(DestinationErr::InvalidAbsoluteUri),
+    }
+}
+
+/// Memoizes the resolved uri of each [`KReference`], for use within a single reconcile.
+///
+/// See [`Destination::resolve_uri_cached`].
+#[derive(Clone, Debug, Default)]
+pub struct ResolutionCache(std::collections::HashMap<ResolutionKey, url::Url>);
+
+/// The api version, group, kind, namespace and name of a [`KReference`].
+type ResolutionKey = (Option<String>, Option<String>, String, Option<String>, String);
+
+impl ResolutionCache {
+    fn key(reference: &KReference) -> ResolutionKey {
+        (
+            reference.api_version.clone(),
+            reference.group.clone(),
+            reference.kind.clone(),
+            reference.namespace.clone(),
+            reference.name.clone(),
+        )
+    }
+}
+
 /// CloudEventOverrides defines arguments for a Source that control the output
 /// format of the CloudEvents produced by the Source.
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
@@ -132,6 +410,43 @@ pub struct CloudEventOverrides {
     /// outbound event. Each `Extensions` key-value pair are set on the event as
     /// an attribute extension independently.
     pub extensions: Option<std::collections::BTreeMap<String, String>>,
+    /// Remove specifies which attributes are removed from the outbound event,
+    /// after `extensions` have been applied.
+    /// Note: this diverges from knative go, which only supports adding or
+    /// overriding attributes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove: Option<Vec<String>>,
+}
+
+/// An outbound event whose extension attributes may be shaped by [`CloudEventOverrides`].
+pub trait ExtensionAttributes {
+    /// Set an extension attribute, overriding any existing value.
+    fn set_extension(&mut self, name: &str, value: &str);
+    /// Remove an extension attribute.
+    fn remove_extension(&mut self, name: &str);
+}
+
+#[cfg(feature = "cloudevents")]
+impl ExtensionAttributes for cloudevents::Event {
+    fn set_extension(&mut self, name: &str, value: &str) {
+        cloudevents::Event::set_extension(self, name, value);
+    }
+
+    fn remove_extension(&mut self, name: &str) {
+        cloudevents::Event::remove_extension(self, name);
+    }
+}
+
+impl CloudEventOverrides {
+    /// Set each of the `extensions` on the event, then delete each of the attributes in `remove`.
+    pub fn apply<E: ExtensionAttributes>(&self, event: &mut E) {
+        for (name, value) in self.extensions.iter().flatten() {
+            event.set_extension(name, value);
+        }
+        for name in self.remove.iter().flatten() {
+            event.remove_extension(name);
+        }
+    }
 }
 
 /// CloudEventAttributes specifies the attributes that a Source
@@ -144,6 +459,15 @@ pub struct CloudEventAttributes {
     source: Option<String>,
 }
 
+impl CloudEventAttributes {
+    pub fn new(type_: &str, source: &str) -> Self {
+        CloudEventAttributes {
+            type_: Some(type_.into()),
+            source: Some(source.into()),
+        }
+    }
+}
+
 /// A baseline [`ConditionType`] for [`SourceStatus`].
 ///
 /// Custom conditions should implement [`SourceConditionType`] in order to be used by
@@ -173,6 +497,13 @@ pub struct SourceStatus<S: SourceConditionType> {
     /// SinkURI is the current active sink URI that has been configured for the
     /// Source.
     pub sink_uri: Option<url::Url>,
+    /// SinkCACerts are Certification Authority (CA) certificates in PEM format
+    /// according to https://www.rfc-editor.org/rfc/rfc7468.
+    #[serde(rename = "sinkCACerts", skip_serializing_if = "Option::is_none")]
+    pub sink_ca_certs: Option<String>,
+    /// SinkAudience is the OIDC audience of the sink.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sink_audience: Option<String>,
     /// CloudEventAttributes are the specific attributes that the Source uses
     /// as part of its CloudEvents.
     pub cloud_event_attributes: Option<Vec<CloudEventAttributes>>,
@@ -201,7 +532,10 @@ pub trait SinkManager<S: SourceConditionType>: SourceConditionManager<S> {
 
     /// Set the condition that the source has no sink configured
     fn mark_no_sink(&mut self, reason: &str, message: Option<String>) {
-        self.source_status().sink_uri = None;
+        let status = self.source_status();
+        status.sink_uri = None;
+        status.sink_ca_certs = None;
+        status.sink_audience = None;
         self.manager().mark_false(S::sinkprovided(), reason, message);
     }
 }
@@ -349,4 +683,555 @@ mod test {
         assert_eq!(status.source_status.sink_uri, Some(uri));
         assert_eq!(status.manager().get_condition(MyCondition::SinkProvided).map(|c| c.is_true()), Some(true))
     }
+
+    #[test]
+    fn destination_from_ref() {
+        let destination = Destination::from_ref(KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some("v1".into()),
+            group: Some("eventing.knative.dev".into()),
+        });
+        let ref_ = destination.ref_.expect("ref is set");
+        assert_eq!(ref_.api_version.as_deref(), Some("eventing.knative.dev/v1"));
+        assert!(ref_.group.is_none());
+        assert_eq!(ref_.name, "default");
+        assert!(destination.uri.is_none());
+    }
+
+    #[test]
+    fn destination_from_uri() {
+        let destination = Destination::from_uri("http://sink.default.svc/path".parse().unwrap()).unwrap();
+        assert!(destination.ref_.is_none());
+        assert_eq!(destination.uri.unwrap(), "http://sink.default.svc/path");
+
+        let url = url::Url::parse("data:text/plain,sink").unwrap();
+        assert!(matches!(Destination::try_from(url), Err(DestinationErr::HttpUriParse(_))));
+        let url = url::Url::parse(&format!("http://sink.default.svc/{}", "a".repeat(u16::MAX as usize))).unwrap();
+        assert!(matches!(Destination::try_from(url), Err(DestinationErr::HttpUriParse(_))));
+    }
+
+    #[async_std::test]
+    async fn destination_resolves_with_lookup() {
+        use std::collections::BTreeMap;
+        use std::future::ready;
+
+        let sinks = BTreeMap::from([
+            ("broker".to_string(), "http://broker-ingress.default.svc.cluster.local/default/broker".parse::<url::Url>().unwrap()),
+        ]);
+        let lookup = |ref_: &KReference| ready(
+            sinks.get(&ref_.name)
+                .cloned()
+                .ok_or_else(|| Error::from(KRefErr::MalformedGVK))
+        );
+        let reference = |name: &str| KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: name.into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        };
+
+        let uri = Destination::from_ref(reference("broker")).resolve_uri_with(lookup).await.unwrap();
+        assert_eq!(uri.as_str(), "http://broker-ingress.default.svc.cluster.local/default/broker");
+
+        let err = Destination::from_ref(reference("missing")).resolve_uri_with(lookup).await;
+        assert!(matches!(
+            err,
+            Err(Error::DestinationError(DestinationErr::RefResolution(KRefErr::MalformedGVK)))
+        ));
+
+        let uri = Destination::from_uri("http://sink.default.svc".parse().unwrap())
+            .unwrap()
+            .resolve_uri_with(lookup)
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc/");
+    }
+
+    #[async_std::test]
+    async fn destination_uri_is_appended_to_ref_path() {
+        let broker = KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        };
+        let resolve = |base: &'static str, uri: &'static str| {
+            let mut destination = Destination::from_ref(broker.clone());
+            destination.uri = Some(uri.parse().unwrap());
+            async move {
+                destination
+                    .resolve_uri_with(|_| std::future::ready(Ok(base.parse().unwrap())))
+                    .await
+                    .unwrap()
+                    .to_string()
+            }
+        };
+
+        let base = "http://broker-ingress.default.svc.cluster.local/default/default";
+        assert_eq!(resolve(base, "/extra").await, format!("{base}/extra"));
+        assert_eq!(resolve(base, "/extra?key=value").await, format!("{base}/extra?key=value"));
+        // trailing slash on the ref's path is not duplicated
+        assert_eq!(resolve("http://sink.default.svc/base/", "/extra").await, "http://sink.default.svc/base/extra");
+        // ref without a path
+        assert_eq!(resolve("http://sink.default.svc", "/extra").await, "http://sink.default.svc/extra");
+    }
+
+    #[async_std::test]
+    async fn destination_resolves_relative_uri_against_ref() {
+        let broker = KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        };
+        let base = "http://broker-ingress.default.svc.cluster.local/default/default";
+        let resolve = |uri: &'static str| {
+            let destination = Destination {
+                ref_: Some(broker.clone()),
+                uri: Some(uri.parse().unwrap()),
+                ..Destination::default()
+            };
+            async move {
+                destination
+                    .resolve_uri_with(|_| std::future::ready(Ok(base.parse().unwrap())))
+                    .await
+                    .unwrap()
+                    .to_string()
+            }
+        };
+
+        assert_eq!(resolve("/path").await, format!("{base}/path"));
+        assert_eq!(resolve("/?key=value").await, format!("{base}?key=value"));
+        // an absolute uri overrides the ref
+        let lookup = |_: &KReference| std::future::ready(Err(Error::from(KRefErr::MalformedGVK)));
+        let uri = Destination {
+            ref_: Some(broker.clone()),
+            uri: Some("https://sink.default.svc/path".parse().unwrap()),
+            ..Destination::default()
+        };
+        let uri = uri
+            .resolve_uri_with(lookup)
+            .await
+            .expect("ref is not resolved");
+        assert_eq!(uri.as_str(), "https://sink.default.svc/path");
+    }
+
+    #[async_std::test]
+    async fn destination_preserves_query_and_fragment() {
+        let lookup = |base: &'static str| {
+            move |_: &KReference| std::future::ready(Ok(base.parse().unwrap()))
+        };
+        let destination = |ref_: Option<KReference>, uri: &str| Destination {
+            ref_,
+            uri: Some(uri.parse().unwrap()),
+            ..Destination::default()
+        };
+        let broker = KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        };
+
+        // query on an absolute uri
+        let uri = destination(None, "http://sink.default.svc/path?key=val&other=1")
+            .resolve_uri_with(lookup("http://unused"))
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc/path?key=val&other=1");
+
+        // query on a uri relative to the ref, keeping the fragment of the ref
+        let uri = destination(Some(broker.clone()), "/extra?key=val")
+            .resolve_uri_with(lookup("http://sink.default.svc/base#frag"))
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc/base/extra?key=val#frag");
+
+        // the query of the relative uri replaces that of the ref
+        let uri = destination(Some(broker.clone()), "/extra?key=val")
+            .resolve_uri_with(lookup("http://sink.default.svc/base?key=old"))
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc/base/extra?key=val");
+
+        // without a query on the relative uri, the query of the ref is kept
+        let uri = destination(Some(broker.clone()), "/extra")
+            .resolve_uri_with(lookup("http://sink.default.svc/base?key=old"))
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc/base/extra?key=old");
+
+        // the fragment of the uri survives deserialization, replacing that of the ref
+        let destination: Destination = serde_json::from_value(serde_json::json!({
+            "ref": broker,
+            "uri": "/extra?key=val#section",
+        })).unwrap();
+        let uri = destination.resolve_uri_with(lookup("http://sink.default.svc/base#frag"))
+            .await
+            .unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc/base/extra?key=val#section");
+        assert_eq!(serde_json::to_value(&destination).unwrap()["uri"], "/extra?key=val#section");
+
+        let destination: Destination = serde_json::from_value(serde_json::json!({
+            "uri": "http://sink.default.svc/path#section",
+        })).unwrap();
+        let uri = destination.resolve_uri_with(lookup("http://unused")).await.unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc/path#section");
+
+        let url = url::Url::parse("http://sink.default.svc/path#section").unwrap();
+        let uri = Destination::from_uri(url.clone()).unwrap().resolve_uri_with(lookup("http://unused")).await.unwrap();
+        assert_eq!(uri, url);
+    }
+
+    #[test]
+    fn validates_sink() {
+        let broker = KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        };
+        let spec = |ref_: Option<KReference>, uri: Option<&str>| SourceSpec {
+            sink: Some(Destination { ref_, uri: uri.map(|u| u.parse().unwrap()), ..Destination::default() }),
+            ce_overrides: None,
+        };
+
+        assert_eq!(SourceSpec::default().validate(), Err(DestinationErr::Empty));
+        assert_eq!(spec(None, None).validate(), Err(DestinationErr::Empty));
+        assert_eq!(
+            spec(Some(broker.clone()), Some("http://sink.default.svc/")).validate(),
+            Err(DestinationErr::AbsoluteUriWithRef)
+        );
+        assert_eq!(spec(None, Some("/extra")).validate(), Err(DestinationErr::RelativeUriWithoutRef));
+
+        assert_eq!(spec(Some(broker.clone()), None).validate(), Ok(()));
+        assert_eq!(spec(Some(broker), Some("/extra")).validate(), Ok(()));
+        assert_eq!(spec(None, Some("http://sink.default.svc/")).validate(), Ok(()));
+    }
+
+    #[async_std::test]
+    async fn rejects_invalid_absolute_uri() {
+        let destination = |uri: &str| Destination { uri: Some(uri.parse().unwrap()), ..Destination::default() };
+        let lookup = |_: &KReference| std::future::ready(Err(Error::from(KRefErr::MalformedGVK)));
+
+        let schemeless = destination("sink.default.svc:8080");
+        assert_eq!(schemeless.validate(), Err(DestinationErr::InvalidAbsoluteUri));
+        let err = schemeless.resolve_uri_with(lookup).await;
+        assert!(matches!(err, Err(Error::DestinationError(DestinationErr::InvalidAbsoluteUri))));
+
+        let valid = destination("http://sink.default.svc:8080/path");
+        assert_eq!(valid.validate(), Ok(()));
+        let uri = valid.resolve_uri_with(lookup).await.unwrap();
+        assert_eq!(uri.as_str(), "http://sink.default.svc:8080/path");
+    }
+
+    #[async_std::test]
+    async fn resolution_error_names_the_failing_field() {
+        let lookup = |_: &KReference| std::future::ready(Err(Error::from(KRefErr::MustBeNamespaced)));
+
+        let err = Destination::from_ref(KReference {
+            kind: "Broker".into(),
+            namespace: None,
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        }).resolve_uri_with(lookup).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DestinationError(DestinationErr::RefResolution(KRefErr::MustBeNamespaced))
+        ));
+        assert_eq!(err.to_string(), "Error destination: destination Ref could not be resolved: must be namespaced");
+
+        // a valid http::Uri may still be an invalid url::Url
+        let destination = Destination { uri: Some("http://sink:99999/".parse().unwrap()), ..Destination::default() };
+        let err = destination.resolve_uri_with(lookup).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::DestinationError(DestinationErr::UriParse(url::ParseError::InvalidPort))
+        ));
+
+        let err = Destination::default().resolve_uri_with(lookup).await.unwrap_err();
+        assert!(matches!(err, Error::DestinationError(DestinationErr::Empty)));
+    }
+
+    #[test]
+    fn destination_uri_schemas_as_uri() {
+        let schema = serde_json::to_value(schemars::schema_for!(Destination)).unwrap();
+        let uri = &schema["properties"]["uri"];
+        assert_eq!(uri["type"], serde_json::json!(["string", "null"]));
+        assert_eq!(uri["format"], "uri");
+        assert!(schema["properties"].get("fragment").is_none());
+    }
+
+    #[derive(Default)]
+    struct MockEvent(std::collections::BTreeMap<String, String>);
+
+    impl ExtensionAttributes for MockEvent {
+        fn set_extension(&mut self, name: &str, value: &str) {
+            self.0.insert(name.into(), value.into());
+        }
+
+        fn remove_extension(&mut self, name: &str) {
+            self.0.remove(name);
+        }
+    }
+
+    #[test]
+    fn ce_overrides_add_and_remove_attributes() {
+        let overrides: CloudEventOverrides = serde_json::from_value(serde_json::json!({
+            "extensions": { "added": "value", "overridden": "new" },
+            "remove": ["removed"]
+        })).unwrap();
+
+        let mut event = MockEvent::default();
+        event.set_extension("overridden", "old");
+        event.set_extension("removed", "value");
+        overrides.apply(&mut event);
+
+        assert_eq!(event.0.get("added").map(String::as_str), Some("value"));
+        assert_eq!(event.0.get("overridden").map(String::as_str), Some("new"));
+        assert!(!event.0.contains_key("removed"));
+    }
+
+    #[cfg(feature = "cloudevents")]
+    #[test]
+    fn ce_overrides_apply_to_cloudevents() {
+        use cloudevents::{EventBuilder, EventBuilderV10, event::ExtensionValue};
+
+        let overrides: CloudEventOverrides = serde_json::from_value(serde_json::json!({
+            "extensions": { "added": "value", "overridden": "new" },
+            "remove": ["removed"]
+        })).unwrap();
+        let mut event = EventBuilderV10::new()
+            .id("0001")
+            .ty("dev.knative.example")
+            .source("http://localhost/source")
+            .extension("overridden", "old")
+            .extension("removed", "value")
+            .build()
+            .unwrap();
+        overrides.apply(&mut event);
+
+        assert_eq!(event.extension("added"), Some(&ExtensionValue::from("value")));
+        assert_eq!(event.extension("overridden"), Some(&ExtensionValue::from("new")));
+        assert_eq!(event.extension("removed"), None);
+    }
+
+    #[test]
+    fn ce_overrides_remove_is_optional() {
+        let overrides: CloudEventOverrides = serde_json::from_value(serde_json::json!({
+            "extensions": { "added": "value" }
+        })).unwrap();
+        assert!(overrides.remove.is_none());
+        assert!(serde_json::to_value(&overrides).unwrap().get("remove").is_none());
+    }
+
+    #[tokio::test]
+    async fn destination_ref_namespace_defaults_to_holder() {
+        use crate::mock::{self, read_mock};
+        use kube::api::DynamicObject;
+
+        let broker = read_mock::<DynamicObject>("default_broker.yaml");
+        let (client, _) = mock::client(vec![broker.clone()]);
+        let destination = Destination::from(KReference {
+            kind: "Broker".into(),
+            namespace: None,
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        });
+
+        assert!(destination.resolve_uri(client.clone()).await.is_err());
+
+        let uri = destination.resolve_uri_for(client, &broker).await
+            .expect("namespace is provided by the holder");
+        assert_eq!(uri.as_str(), "http://broker-ingress.default.svc.cluster.local/default/default");
+    }
+
+    #[tokio::test]
+    async fn destination_resolution_is_cached() {
+        use crate::mock::{self, read_mock};
+
+        let (client, requests) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let destination = Destination::from(KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        });
+        let mut cache = ResolutionCache::default();
+
+        let first = destination.resolve_uri_cached(client.clone(), &mut cache).await.unwrap();
+        let served = requests.lock().unwrap().len();
+        assert!(served > 0);
+
+        let second = destination.resolve_uri_cached(client, &mut cache).await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(requests.lock().unwrap().len(), served, "second resolve hits the cache");
+    }
+
+    #[tokio::test]
+    async fn resolution_cache_distinguishes_groups() {
+        use crate::mock::{self, read_mock};
+        use kube::api::DynamicObject;
+
+        let broker = read_mock::<DynamicObject>("default_broker.yaml");
+        let mut other = broker.clone();
+        other.types.as_mut().unwrap().api_version = "example.dev/v1".into();
+        other.data["status"]["address"]["url"] = "http://other.default.svc.cluster.local/".into();
+        let (client, _) = mock::client(vec![broker, other]);
+        let destination = |group: &str| Destination::from(KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: None,
+            group: Some(group.into()),
+        });
+        let mut cache = ResolutionCache::default();
+
+        let first = destination("eventing.knative.dev")
+            .resolve_uri_cached(client.clone(), &mut cache).await.unwrap();
+        let second = destination("example.dev")
+            .resolve_uri_cached(client, &mut cache).await.unwrap();
+        assert_eq!(first.as_str(), "http://broker-ingress.default.svc.cluster.local/default/default");
+        assert_eq!(second.as_str(), "http://other.default.svc.cluster.local/");
+    }
+
+    #[tokio::test]
+    async fn destination_resolves_knative_service() {
+        use crate::mock::{self, read_mock};
+
+        let service = read_mock("knative_service.yaml");
+        let (client, _) = mock::client(vec![service]);
+        let destination = Destination::from(KReference {
+            kind: "Service".into(),
+            namespace: Some("default".into()),
+            name: "hello".into(),
+            api_version: Some("serving.knative.dev/v1".into()),
+            group: None,
+        });
+
+        let uri = destination.resolve_uri(client).await.expect("knative service resolves");
+        assert_eq!(uri.as_str(), "https://hello.default.example.com/");
+    }
+
+    #[tokio::test]
+    async fn destination_resolves_ref_and_relative_uri() {
+        use crate::mock::{self, read_mock};
+
+        let (client, _) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let destination = Destination {
+            ref_: Some(KReference {
+                kind: "Broker".into(),
+                namespace: Some("default".into()),
+                name: "default".into(),
+                api_version: Some("eventing.knative.dev/v1".into()),
+                group: None,
+            }),
+            uri: Some("/extra/path".parse().unwrap()),
+            ..Destination::default()
+        };
+
+        let uri = destination.resolve_uri(client).await.expect("broker resolves");
+        assert_eq!(uri.as_str(), "http://broker-ingress.default.svc.cluster.local/default/default/extra/path");
+    }
+
+    #[test]
+    fn destination_round_trips_ca_certs_and_audience() {
+        let yaml = r#"
+ref:
+  kind: Broker
+  namespace: default
+  name: default
+  apiVersion: eventing.knative.dev/v1
+uri: /extra
+CACerts: |
+  -----BEGIN CERTIFICATE-----
+  -----END CERTIFICATE-----
+audience: eventing.knative.dev/broker/default/default
+"#;
+        let destination: Destination = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(destination.ref_.as_ref().unwrap().name, "default");
+        assert_eq!(destination.uri.as_ref().unwrap(), "/extra");
+        assert!(destination.ca_certs.as_deref().unwrap().starts_with("-----BEGIN CERTIFICATE-----"));
+        assert_eq!(destination.audience.as_deref(), Some("eventing.knative.dev/broker/default/default"));
+
+        let json = serde_json::to_value(&destination).unwrap();
+        assert_eq!(json, serde_yaml::from_str::<serde_json::Value>(yaml).unwrap());
+        // unset fields are omitted
+        let json = serde_json::to_value(Destination::from_uri("http://sink.default.svc/".parse().unwrap()).unwrap()).unwrap();
+        assert!(json.get("CACerts").is_none() && json.get("audience").is_none());
+    }
+
+    #[tokio::test]
+    async fn destination_resolves_to_addressable() {
+        use crate::mock::{self, read_mock};
+
+        let (client, _) = mock::client(vec![read_mock("default_broker.yaml")]);
+        let mut destination = Destination::from_ref(KReference {
+            kind: "Broker".into(),
+            namespace: Some("default".into()),
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        });
+        destination.audience = Some("broker".into());
+
+        let address = destination.resolve_addressable(client).await.expect("broker resolves");
+        assert_eq!(address.url.unwrap().as_str(), "http://broker-ingress.default.svc.cluster.local/default/default");
+        assert_eq!(address.audience.as_deref(), Some("broker"));
+        assert_eq!(address.ca_certs, None);
+    }
+
+    #[tokio::test]
+    async fn destination_addressable_defaults_to_referent() {
+        use crate::mock;
+
+        let broker = serde_json::from_value(serde_json::json!({
+            "apiVersion": "eventing.knative.dev/v1",
+            "kind": "Broker",
+            "metadata": { "name": "default", "namespace": "default" },
+            "status": {
+                "address": {
+                    "url": "https://broker-ingress.knative-eventing.svc.cluster.local/default/default",
+                    "CACerts": "-----BEGIN CERTIFICATE-----",
+                    "audience": "broker",
+                },
+            },
+        })).unwrap();
+        let (client, _) = mock::client(vec![broker]);
+        let mut destination = Destination::from_ref(KReference {
+            kind: "Broker".into(),
+            namespace: None,
+            name: "default".into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        });
+        destination.uri = Some("/extra".parse().unwrap());
+        let holder = kube::api::DynamicObject::new("holder", &kube::api::ApiResource::erase::<k8s_openapi::api::core::v1::Pod>(&()))
+            .within("default");
+
+        // the certificates and audience come only from the referent
+        let address = destination.resolve_addressable_for(client.clone(), &holder).await.expect("broker resolves");
+        assert_eq!(
+            address.url.unwrap().as_str(),
+            "https://broker-ingress.knative-eventing.svc.cluster.local/default/default/extra"
+        );
+        assert_eq!(address.ca_certs.as_deref(), Some("-----BEGIN CERTIFICATE-----"));
+        assert_eq!(address.audience.as_deref(), Some("broker"));
+
+        // those of the destination take precedence
+        destination.audience = Some("sink".into());
+        let address = destination.resolve_addressable_for(client, &holder).await.expect("broker resolves");
+        assert_eq!(address.ca_certs.as_deref(), Some("-----BEGIN CERTIFICATE-----"));
+        assert_eq!(address.audience.as_deref(), Some("sink"));
+    }
 }
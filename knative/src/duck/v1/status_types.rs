@@ -1,14 +1,15 @@
 #![allow(dead_code)]
-use knative_conditions::{Conditions, ConditionAccessor, ConditionType};
+use knative_conditions::{Condition, Conditions, ConditionAccessor, ConditionStatus, ConditionType};
 use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
-
+use serde::de::DeserializeOwned;
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Status<C: ConditionType> {
     /// ObservedGeneration is the 'Generation' of the Service that
-    /// was last processed by the controller.
+    /// was last processed by the controller. Absent if the resource has never been reconciled.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub observed_generation: Option<i64>,
     /// Conditions the latest available observations of a resource's current state.
     pub conditions: Option<Conditions<C>>,
@@ -22,26 +23,180 @@ pub struct Status<C: ConditionType> {
 impl<C: ConditionType> Default for Status<C> {
     fn default() -> Status<C> {
         Status {
-            observed_generation: Some(0i64),
+            observed_generation: None,
             conditions: Some(Conditions::default()),
             annotations: None
         }
     }
 }
 
+/// A summary of the overall state of a resource, derived from its [`Status`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResourceState {
+    /// The happy condition is true.
+    Ready,
+    /// The happy condition is false, for the dominant reason.
+    NotReady { reason: Option<String> },
+    /// The happy condition is unknown, for the dominant reason.
+    Unknown { reason: Option<String> },
+    /// The resource has not been reconciled, or reconciliation has not yet reached a verdict.
+    Reconciling,
+    /// The status has not yet been updated for the latest generation of the resource, so the state
+    /// of the resource is not known to be current.
+    Stale,
+}
+
+impl<C: ConditionType> Status<C> {
+    /// Record the `metadata.generation` of the resource that is being reconciled.
+    pub fn observe_generation(&mut self, generation: i64) {
+        self.observed_generation = Some(generation);
+    }
+
+    /// Whether the resource is ready overall, without the mutable borrow of
+    /// [`ConditionAccessor::is_ready`]. A status without conditions is not ready.
+    pub fn is_ready_ref(&self) -> bool {
+        matches!(&self.conditions, Some(conditions) if conditions.is_happy())
+    }
+
+    /// Whether the status has not yet been updated for `meta_generation`, the
+    /// `metadata.generation` of the resource.
+    pub fn is_stale(&self, meta_generation: i64) -> bool {
+        !matches!(self.observed_generation, Some(observed) if observed >= meta_generation)
+    }
+
+    /// Summarize the state of the resource as of `meta_generation`, the `metadata.generation` of
+    /// the resource. A status that has not yet observed that generation is [`ResourceState::Stale`],
+    /// unless the resource has never been reconciled.
+    pub fn summary_at(&self, meta_generation: i64) -> ResourceState {
+        match self.observed_generation {
+            Some(_) if self.is_stale(meta_generation) => ResourceState::Stale,
+            _ => self.summary(),
+        }
+    }
+
+    /// Carry forward the state of `previous` that was not set this round, for a read-modify-write
+    /// reconcile. Annotations and conditions already set are kept, as is the observed generation.
+    pub fn merge_from(&mut self, previous: &Status<C>) {
+        if self.observed_generation.is_none() {
+            self.observed_generation = previous.observed_generation;
+        }
+        match (&mut self.conditions, &previous.conditions) {
+            (Some(conditions), Some(prev)) => conditions.merge_from(prev),
+            (conditions @ None, prev) => *conditions = prev.clone(),
+            (Some(_), None) => {}
+        }
+        if let Some(prev) = &previous.annotations {
+            let annotations = self.annotations.get_or_insert_with(Default::default);
+            for (key, value) in prev {
+                annotations.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+    }
+
+    /// Summarize the state of the resource from its happy condition.
+    pub fn summary(&self) -> ResourceState {
+        let happy = match (self.observed_generation, &self.conditions) {
+            (Some(_), Some(conditions)) => conditions.get(&C::happy()).cloned(),
+            _ => None,
+        };
+
+        match happy {
+            Some(Condition { status: ConditionStatus::True, .. }) => ResourceState::Ready,
+            Some(Condition { status: ConditionStatus::False, reason, .. }) => {
+                ResourceState::NotReady { reason }
+            }
+            Some(Condition { status: ConditionStatus::Unknown, reason: Some(reason), .. }) => {
+                ResourceState::Unknown { reason: Some(reason) }
+            }
+            Some(Condition { status: ConditionStatus::Unknown, reason: None, .. }) | None => {
+                ResourceState::Reconciling
+            }
+        }
+    }
+}
+
 impl<C: ConditionType> ConditionAccessor<C> for Status<C> {
     fn conditions(&mut self) -> &mut Conditions<C> {
         self.conditions.get_or_insert(Conditions::default())
     }
 }
 
+/// Extract the typed [`Status`] of any object, such as the status of a source whose concrete type
+/// is unknown to a generic controller.
+///
+/// Returns `None` if the object has no status, or the status is malformed.
+pub fn extract_status<C>(obj: &kube::api::DynamicObject) -> Option<Status<C>>
+where
+    C: ConditionType + DeserializeOwned,
+{
+    let status = obj.data.get("status")?;
+    serde_json::from_value(status.clone()).ok()
+}
+
+/// A resource with a status, so that helpers may read and write the status of any kind.
+pub trait HasStatus {
+    type Status: Default;
+
+    /// Returns the status of the resource, if it has been set.
+    fn status(&self) -> Option<&Self::Status>;
+
+    /// Returns the status of the resource for management, initializing it if absent.
+    fn status_mut(&mut self) -> &mut Self::Status;
+}
+
+/// The field manager used for server-side apply when none is configured.
+pub const DEFAULT_FIELD_MANAGER: &str = "knative-rs";
+
+/// The identity a resource's controller uses to manage fields with server-side apply.
+///
+/// Controllers that run alongside the knative go implementation should use a distinct field
+/// manager, to avoid conflicting over ownership of fields.
+pub trait FieldManager {
+    const FIELD_MANAGER: &'static str = DEFAULT_FIELD_MANAGER;
+}
+
+/// Server-side apply the `status` of the resource `name`, as the [`FieldManager`] of `K`.
+pub async fn patch_status<K, S>(
+    api: &kube::Api<K>,
+    name: &str,
+    status: &S,
+) -> Result<K, crate::error::Error>
+where
+    K: kube::Resource<DynamicType = ()> + FieldManager + Clone + DeserializeOwned + std::fmt::Debug,
+    S: Serialize,
+{
+    patch_status_as(api, name, status, K::FIELD_MANAGER).await
+}
+
+/// Server-side apply the `status` of the resource `name`, as `field_manager`.
+pub async fn patch_status_as<K, S>(
+    api: &kube::Api<K>,
+    name: &str,
+    status: &S,
+    field_manager: &str,
+) -> Result<K, crate::error::Error>
+where
+    K: kube::Resource<DynamicType = ()> + Clone + DeserializeOwned + std::fmt::Debug,
+    S: Serialize,
+{
+    use kube::api::{Patch, PatchParams};
+
+    let patch = serde_json::json!({
+        "apiVersion": K::api_version(&()),
+        "kind": K::kind(&()),
+        "status": status,
+    });
+    let params = PatchParams::apply(field_manager).force();
+    Ok(api.patch_status(name, &params, &Patch::Apply(&patch)).await?)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use knative_derive::ConditionType;
     use enumset::EnumSetType;
 
-    #[derive(ConditionType, EnumSetType, Debug)]
+    #[derive(ConditionType, EnumSetType, Serialize, Deserialize, Debug, JsonSchema)]
     enum CustomCondition {
         Succeeded,
         SomethingElse
@@ -83,6 +238,113 @@ mod test {
         assert_eq!(status.is_ready(), false);
     }
 
+    #[test]
+    fn can_mark_custom_status_ready() {
+        let mut custom_status = CustomStatus {
+            status: Status::default()
+        };
+        let status = &mut custom_status.status;
+        assert!(!status.is_ready());
+
+        status.mark_ready();
+        assert!(status.is_ready());
+
+        // and may be marked not ready again
+        status.mark_false("Failed", None);
+        assert!(!status.is_ready());
+        status.mark_ready();
+        assert!(status.is_ready());
+    }
+
+    #[test]
+    fn reads_readiness_immutably() {
+        let mut status = Status::<CustomCondition>::default();
+        assert!(!status.is_ready_ref());
+
+        status.mark_somethingelse();
+        let status = &status;
+        assert!(status.is_ready_ref());
+
+        let status = Status::<CustomCondition> { conditions: None, ..Default::default() };
+        assert!(!status.is_ready_ref());
+    }
+
+    #[test]
+    fn never_reconciled_status_omits_observed_generation() {
+        let mut status = Status::<CustomCondition>::default();
+        let json = serde_json::to_value(&status).unwrap();
+        assert!(json.get("observedGeneration").is_none());
+
+        status.observe_generation(1);
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["observedGeneration"], 1);
+    }
+
+    #[test]
+    fn summarizes_resource_state() {
+        let mut status = Status::<CustomCondition>::default();
+        // never reconciled
+        assert_eq!(status.summary(), ResourceState::Reconciling);
+
+        // reconciled, but no verdict reached
+        status.observe_generation(1);
+        assert_eq!(status.summary(), ResourceState::Reconciling);
+
+        status.mark_somethingelse();
+        assert_eq!(status.summary(), ResourceState::Ready);
+
+        status.mark_false("Broken", None);
+        assert_eq!(status.summary(), ResourceState::NotReady { reason: Some("Broken".into()) });
+
+        status.mark_unknown_with_message("Waiting", None);
+        assert_eq!(status.summary(), ResourceState::Unknown { reason: Some("Waiting".into()) });
+    }
+
+    #[test]
+    fn status_is_stale_until_generation_is_observed() {
+        let mut status = Status::<CustomCondition>::default();
+        assert!(status.is_stale(1));
+        assert_eq!(status.summary_at(1), ResourceState::Reconciling);
+
+        status.observe_generation(1);
+        status.mark_somethingelse();
+        assert!(!status.is_stale(1));
+        assert_eq!(status.summary_at(1), ResourceState::Ready);
+
+        // the resource was updated, but the controller has yet to observe it
+        assert!(status.is_stale(2));
+        assert_eq!(status.summary_at(2), ResourceState::Stale);
+
+        status.observe_generation(2);
+        assert_eq!(status.summary_at(2), ResourceState::Ready);
+    }
+
+    #[test]
+    fn merges_annotations_from_previous_status() {
+        use std::collections::BTreeMap;
+
+        let mut previous = Status::<CustomCondition>::default();
+        previous.observe_generation(3);
+        previous.mark_somethingelse();
+        previous.annotations = Some(BTreeMap::from([
+            ("kept".to_string(), "previous".to_string()),
+            ("changed".to_string(), "previous".to_string()),
+        ]));
+
+        let mut status = Status::<CustomCondition> {
+            observed_generation: None,
+            conditions: None,
+            annotations: Some(BTreeMap::from([("changed".to_string(), "current".to_string())])),
+        };
+        status.merge_from(&previous);
+
+        let annotations = status.annotations.as_ref().unwrap();
+        assert_eq!(annotations["kept"], "previous");
+        assert_eq!(annotations["changed"], "current");
+        assert_eq!(status.observed_generation, Some(3));
+        assert!(status.is_ready());
+    }
+
     #[test]
     fn can_init_with_custom_condition_state() {
         use knative_conditions::{Condition, ConditionStatus};
@@ -117,4 +379,72 @@ mod test {
             }
         };
     }
+
+    #[test]
+    fn extracts_status_from_dynamic_object() {
+        use crate::source_types::{SourceCondition, SourceStatus, SinkManager};
+
+        let mut source_status = SourceStatus::<SourceCondition>::default();
+        source_status.status.observe_generation(2);
+        source_status.mark_sink("http://sink.default.svc.cluster.local/".parse().unwrap());
+
+        let mut obj: kube::api::DynamicObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "sources.knative.dev/v1",
+            "kind": "PingSource",
+            "metadata": { "name": "ping", "namespace": "default" },
+            "spec": {},
+            "status": source_status,
+        })).unwrap();
+
+        let status = extract_status::<SourceCondition>(&obj).expect("status is present");
+        assert_eq!(status.observed_generation, Some(2));
+        assert_eq!(status.summary(), ResourceState::Ready);
+
+        obj.data["status"] = serde_json::json!({ "conditions": "not a list" });
+        assert!(extract_status::<SourceCondition>(&obj).is_none());
+
+        obj.data.as_object_mut().unwrap().remove("status");
+        assert!(extract_status::<SourceCondition>(&obj).is_none());
+    }
+
+    #[derive(kube::CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[kube(group = "custom.dev", version = "v1", kind = "Custom", status = "CustomResourceStatus", namespaced)]
+    struct CustomSpec {}
+
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+    struct CustomResourceStatus {
+        #[serde(flatten)]
+        status: Status<CustomCondition>,
+    }
+
+    impl FieldManager for Custom {}
+
+    fn patch_client(expected_manager: &'static str) -> kube::Client {
+        use http::{Method, Request, Response};
+        use hyper::Body;
+
+        let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+        tokio::spawn(async move {
+            while let Some((request, send)) = handle.next_request().await {
+                assert_eq!(request.method(), Method::PATCH);
+                assert_eq!(request.uri().path(), "/apis/custom.dev/v1/namespaces/default/customs/custom/status");
+                let query = request.uri().query().unwrap();
+                assert!(query.contains(&format!("fieldManager={expected_manager}")), "{query}");
+                let custom = Custom::new("custom", CustomSpec {});
+                send.send_response(Response::new(Body::from(serde_json::to_vec(&custom).unwrap())));
+            }
+        });
+        kube::Client::new(service, "default")
+    }
+
+    #[tokio::test]
+    async fn patches_status_as_field_manager() {
+        let status = CustomResourceStatus::default();
+
+        let api = kube::Api::<Custom>::namespaced(patch_client(DEFAULT_FIELD_MANAGER), "default");
+        patch_status(&api, "custom", &status).await.unwrap();
+
+        let api = kube::Api::<Custom>::namespaced(patch_client("my-controller"), "default");
+        patch_status_as(&api, "custom", &status, "my-controller").await.unwrap();
+    }
 }
@@ -0,0 +1,30 @@
+use knative_conditions::ConditionStatus;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// SubscribableStatus is the schema for the subscribable's status portion of the status
+/// section of the resource.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribableStatus {
+    /// This is the list of subscription's statuses for this channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscribers: Option<Vec<SubscriberStatus>>,
+}
+
+/// SubscriberStatus defines the status of a single subscriber to a Channel.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriberStatus {
+    /// UID is used to understand the origin of the subscriber.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    /// Generation of the origin of the subscriber with uid:UID.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub observed_generation: Option<i64>,
+    /// Status of the subscriber.
+    pub ready: ConditionStatus,
+    /// A human readable message indicating details of Ready status.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
@@ -1,6 +1,7 @@
 use crate::duck::v1::{
     addressable_type::AddressableErr,
-    knative_reference::KRefErr,
+    delivery_types::DeliveryErr,
+    knative_reference::{DiscoveryErr, KRefErr},
     source_types::DestinationErr,
 };
 use thiserror::Error;
@@ -23,5 +24,11 @@ pub enum Error {
     KReferenceError(#[from] KRefErr),
     /// Addressable errors
     #[error("Error addressable: {0}")]
-    AddressableError(#[from] AddressableErr)
+    AddressableError(#[from] AddressableErr),
+    /// Discovery errors
+    #[error("Error discovery: {0}")]
+    DiscoveryError(#[from] DiscoveryErr),
+    /// Delivery errors
+    #[error("Error delivery: {0}")]
+    DeliveryError(#[from] DeliveryErr),
 }
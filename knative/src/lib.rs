@@ -1,6 +1,12 @@
 #![doc = include_str!("../README.md")]
 mod duck;
 pub mod error;
+#[cfg(test)]
+mod mock;
+pub mod source;
+pub mod strict;
+#[cfg(feature = "runtime")]
+pub mod watch;
 
 // expose only v1 types
 pub use duck::v1::*;
@@ -13,3 +19,25 @@ pub mod derive {
     pub use knative_derive::ConditionType;
     pub use enumset::EnumSetType;
 }
+
+/// Common imports for managing the conditions of a resource.
+///
+/// ```rust
+/// use knative::prelude::*;
+///
+/// #[derive(ConditionType, EnumSetType, Debug)]
+/// enum MyCondition {
+///     Ready,
+///     #[dependent]
+///     Important,
+/// }
+///
+/// let mut conditions = Conditions::<MyCondition>::default();
+/// let mut manager = ConditionManager::new(&mut conditions);
+/// manager.mark_true(MyCondition::Important);
+/// assert!(manager.is_happy());
+/// ```
+pub mod prelude {
+    pub use knative_conditions::prelude::*;
+    pub use crate::derive::*;
+}
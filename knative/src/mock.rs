@@ -0,0 +1,146 @@
+//! A mock API server for tests that need a [`kube::Client`].
+use http::{Request, Response, StatusCode};
+use hyper::Body;
+use kube::api::{DynamicObject, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+/// The uris requested of the mock API server, in order.
+pub type Requests = Arc<Mutex<Vec<String>>>;
+
+pub fn mock_path() -> String {
+    format!("{}/{}/",
+        env!("CARGO_MANIFEST_DIR"),
+        "../test/mock",
+    )
+}
+
+pub fn read_mock<T: Resource + DeserializeOwned>(filename: &str) -> T {
+    let path = mock_path() + filename;
+    let yaml = fs::read_to_string(path).expect("path to mock");
+    serde_yaml::from_str(&yaml).unwrap()
+}
+
+fn plural(kind: &str) -> String {
+    kind.to_lowercase() + "s"
+}
+
+fn api_path(api_version: &str) -> String {
+    match api_version.contains('/') {
+        true => format!("/apis/{api_version}"),
+        false => format!("/api/{api_version}"),
+    }
+}
+
+/// Returns the response body for a request path, if the API server knows of it.
+fn respond(objects: &[DynamicObject], path: &str) -> Option<Value> {
+    // group discovery
+    if path == "/apis" {
+        let mut groups = Vec::<(&str, Vec<&str>)>::new();
+        for api_version in objects.iter().filter_map(|o| Some(o.types.as_ref()?.api_version.as_str())) {
+            if let Some((group, version)) = api_version.split_once('/') {
+                match groups.iter_mut().find(|(g, _)| *g == group) {
+                    Some((_, versions)) if !versions.contains(&version) => versions.push(version),
+                    Some(_) => {}
+                    None => groups.push((group, vec![version])),
+                }
+            }
+        }
+        let group_version = |group: &str, version: &str| json!({
+            "groupVersion": format!("{group}/{version}"),
+            "version": version,
+        });
+        return Some(json!({
+            "kind": "APIGroupList",
+            "apiVersion": "v1",
+            "groups": groups.iter().map(|(group, versions)| json!({
+                "name": group,
+                "versions": versions.iter().map(|v| group_version(group, v)).collect::<Vec<_>>(),
+                "preferredVersion": group_version(group, versions[0]),
+            })).collect::<Vec<_>>(),
+        }))
+    }
+
+    // resource discovery
+    let discovered = objects.iter()
+        .filter_map(|o| o.types.as_ref())
+        .filter(|t| api_path(&t.api_version) == path)
+        .map(|t| json!({
+            "name": plural(&t.kind),
+            "singularName": t.kind.to_lowercase(),
+            "namespaced": true,
+            "kind": t.kind,
+            "verbs": ["get", "list", "watch"],
+        }))
+        .collect::<Vec<_>>();
+    if !discovered.is_empty() {
+        let group_version = path.trim_start_matches("/apis/").trim_start_matches("/api/");
+        return Some(json!({
+            "kind": "APIResourceList",
+            "apiVersion": "v1",
+            "groupVersion": group_version,
+            "resources": discovered,
+        }))
+    }
+
+    // get and list
+    let mut items = Vec::new();
+    for (obj, t) in objects.iter().filter_map(|o| Some((o, o.types.as_ref()?))) {
+        let collection = format!(
+            "{}/namespaces/{}/{}",
+            api_path(&t.api_version),
+            obj.namespace().unwrap_or_else(|| "default".into()),
+            plural(&t.kind)
+        );
+        if Some(path) == obj.meta().name.as_ref().map(|n| format!("{collection}/{n}")).as_deref() {
+            return serde_json::to_value(obj).ok()
+        } else if path == collection {
+            items.push(obj);
+        }
+    }
+    let first = items.first()?.types.as_ref()?;
+    Some(json!({
+        "apiVersion": first.api_version,
+        "kind": format!("{}List", first.kind),
+        "metadata": {},
+        "items": items,
+    }))
+}
+
+/// Returns a [`kube::Client`] backed by a mock API server that serves group and resource
+/// discovery, get and list requests for `objects`, along with a log of the requests it has
+/// served.
+///
+/// Must be called from within a tokio runtime.
+pub fn client(objects: Vec<DynamicObject>) -> (kube::Client, Requests) {
+    let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+    let requests = Requests::default();
+    let log = requests.clone();
+
+    tokio::spawn(async move {
+        while let Some((request, send)) = handle.next_request().await {
+            log.lock().unwrap().push(request.uri().to_string());
+            let response = match respond(&objects, request.uri().path()) {
+                Some(body) => Response::builder()
+                    .status(StatusCode::OK)
+                    .body(Body::from(serde_json::to_vec(&body).unwrap())),
+                None => Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::from(serde_json::to_vec(&json!({
+                        "kind": "Status",
+                        "apiVersion": "v1",
+                        "metadata": {},
+                        "status": "Failure",
+                        "message": format!("{} not found", request.uri().path()),
+                        "reason": "NotFound",
+                        "code": 404,
+                    })).unwrap())),
+            };
+            send.send_response(response.unwrap());
+        }
+    });
+
+    (kube::Client::new(service, "default"), requests)
+}
@@ -0,0 +1,208 @@
+//! Reconciliation of the sink of a source.
+use crate::{
+    error::Error,
+    source_types::{CloudEventAttributes, SinkManager, SourceConditionType, SourceSpec, SourceStatus},
+};
+
+/// A resource that embeds a [`SourceSpec`] and [`SourceStatus`].
+pub trait SourceDuck: kube::Resource {
+    type Condition: SourceConditionType;
+
+    /// Return the [`SourceSpec`] of your CRD Spec type.
+    fn source_spec(&self) -> &SourceSpec;
+
+    /// Return the [`SourceStatus`] of your CRD Status type, initializing it if absent.
+    fn source_status_mut(&mut self) -> &mut SourceStatus<Self::Condition>;
+
+    /// The attributes of the CloudEvents that the source produces.
+    fn cloud_event_attributes(&self) -> Vec<CloudEventAttributes> {
+        Vec::new()
+    }
+}
+
+/// Resolve the sink of the `source` and record it in the status, along with the CA certs and
+/// audience of its address and the CloudEvent attributes of the source.
+///
+/// The CA certs and audience of the sink [`Destination`](crate::source_types::Destination) take
+/// precedence over those of the address of its ref, see
+/// [`Destination::resolve_addressable`](crate::source_types::Destination::resolve_addressable).
+///
+/// A sink that can not be resolved, including one whose address is malformed, is marked as no
+/// sink. Only errors from the client, other than the sink not being found, are returned.
+pub async fn reconcile_source<S: SourceDuck>(source: &mut S, client: kube::Client) -> Result<(), Error> {
+    let attributes = source.cloud_event_attributes();
+    source.source_status_mut().cloud_event_attributes = (!attributes.is_empty()).then_some(attributes);
+
+    let sink = match source.source_spec().sink.clone() {
+        Some(sink) => sink,
+        None => {
+            source.source_status_mut().mark_no_sink("SinkMissing", Some("sink is not set".into()));
+            return Ok(())
+        }
+    };
+    let resolved = sink.resolve_addressable_for(client, &*source).await;
+
+    let status = source.source_status_mut();
+    match resolved {
+        Ok(address) => {
+            if let Some(uri) = address.url {
+                status.mark_sink(uri);
+            }
+            status.sink_ca_certs = address.ca_certs;
+            status.sink_audience = address.audience;
+        }
+        Err(Error::KubeError(kube::Error::Api(res))) if res.code == 404 => {
+            status.mark_no_sink("SinkNotFound", Some(res.message));
+        }
+        Err(Error::KubeError(e)) => return Err(e.into()),
+        Err(e) => status.mark_no_sink("SinkNotResolved", Some(e.to_string())),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        knative_reference::KReference,
+        mock,
+        source_types::{Destination, SourceCondition},
+    };
+    use knative_conditions::ConditionAccessor;
+    use kube::{api::DynamicObject, CustomResource};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+    #[kube(group = "sources.example.dev", version = "v1", kind = "TestSource", status = "TestSourceStatus", namespaced)]
+    struct TestSourceSpec {
+        #[serde(flatten)]
+        source_spec: SourceSpec,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+    struct TestSourceStatus {
+        #[serde(flatten)]
+        source_status: SourceStatus<SourceCondition>,
+    }
+
+    impl SourceDuck for TestSource {
+        type Condition = SourceCondition;
+
+        fn source_spec(&self) -> &SourceSpec {
+            &self.spec.source_spec
+        }
+
+        fn source_status_mut(&mut self) -> &mut SourceStatus<SourceCondition> {
+            &mut self.status.get_or_insert_with(Default::default).source_status
+        }
+
+        fn cloud_event_attributes(&self) -> Vec<CloudEventAttributes> {
+            vec![CloudEventAttributes::new("dev.example.test", "/apis/v1/namespaces/default/testsources/test")]
+        }
+    }
+
+    fn source(broker: &str) -> TestSource {
+        let mut source = TestSource::new("test", TestSourceSpec {
+            source_spec: SourceSpec {
+                sink: Some(Destination::from_ref(KReference {
+                    kind: "Broker".into(),
+                    namespace: None,
+                    name: broker.into(),
+                    api_version: Some("eventing.knative.dev/v1".into()),
+                    group: None,
+                })),
+                ce_overrides: None,
+            },
+        });
+        source.metadata.namespace = Some("default".into());
+        source
+    }
+
+    fn uri_sink() -> TestSource {
+        let mut source = source("default");
+        let mut sink = Destination::from_uri("https://sink.example.com/".parse().unwrap()).unwrap();
+        sink.ca_certs = Some("-----BEGIN CERTIFICATE-----".into());
+        sink.audience = Some("sink".into());
+        source.spec.source_spec.sink = Some(sink);
+        source
+    }
+
+    fn broker() -> DynamicObject {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "eventing.knative.dev/v1",
+            "kind": "Broker",
+            "metadata": { "name": "default", "namespace": "default" },
+            "status": {
+                "address": {
+                    "url": "https://broker-ingress.knative-eventing.svc.cluster.local/default/default",
+                    "CACerts": "-----BEGIN CERTIFICATE-----",
+                    "audience": "broker",
+                },
+            },
+        })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reconciles_sink() {
+        let (client, _) = mock::client(vec![broker()]);
+        let mut source = source("default");
+
+        reconcile_source(&mut source, client).await.unwrap();
+
+        let status = &mut source.status.as_mut().unwrap().source_status;
+        assert!(status.is_ready());
+        assert_eq!(
+            status.sink_uri.as_ref().map(url::Url::as_str),
+            Some("https://broker-ingress.knative-eventing.svc.cluster.local/default/default")
+        );
+        assert_eq!(status.sink_ca_certs.as_deref(), Some("-----BEGIN CERTIFICATE-----"));
+        assert_eq!(status.sink_audience.as_deref(), Some("broker"));
+        assert_eq!(status.cloud_event_attributes.as_ref().map(Vec::len), Some(1));
+    }
+
+    #[tokio::test]
+    async fn reconciles_uri_sink_with_its_certs() {
+        let (client, _) = mock::client(vec![]);
+        let mut source = uri_sink();
+
+        reconcile_source(&mut source, client).await.unwrap();
+
+        let status = &mut source.status.as_mut().unwrap().source_status;
+        assert!(status.is_ready());
+        assert_eq!(status.sink_uri.as_ref().map(url::Url::as_str), Some("https://sink.example.com/"));
+        assert_eq!(status.sink_ca_certs.as_deref(), Some("-----BEGIN CERTIFICATE-----"));
+        assert_eq!(status.sink_audience.as_deref(), Some("sink"));
+    }
+
+    #[tokio::test]
+    async fn marks_no_sink_when_address_is_malformed() {
+        let mut broker = broker();
+        broker.data["status"]["address"]["url"] = "not a url".into();
+        let (client, _) = mock::client(vec![broker]);
+        let mut source = source("default");
+
+        reconcile_source(&mut source, client).await.expect("malformed sink is not an error");
+
+        let status = &mut source.status.as_mut().unwrap().source_status;
+        assert!(!status.is_ready());
+        let sink = status.manager().get_condition(SourceCondition::SinkProvided).cloned().unwrap();
+        assert_eq!(sink.reason.as_deref(), Some("SinkNotResolved"));
+    }
+
+    #[tokio::test]
+    async fn marks_no_sink_when_unresolvable() {
+        let (client, _) = mock::client(vec![broker()]);
+        let mut source = source("missing");
+        source.source_status_mut().mark_sink("http://stale.default.svc.cluster.local/".parse().unwrap());
+
+        reconcile_source(&mut source, client).await.expect("missing sink is not an error");
+
+        let status = &mut source.status.as_mut().unwrap().source_status;
+        assert!(!status.is_ready());
+        assert!(status.sink_uri.is_none());
+        let sink = status.manager().get_condition(SourceCondition::SinkProvided).cloned().unwrap();
+        assert_eq!(sink.reason.as_deref(), Some("SinkNotFound"));
+    }
+}
@@ -0,0 +1,155 @@
+//! Strict deserialization of spec types, rejecting fields that are unknown rather than silently
+//! ignoring them.
+//!
+//! `#[serde(deny_unknown_fields)]` can not be combined with `#[serde(flatten)]`, which the spec
+//! types use to embed one another, so the fields of each object are checked against
+//! [`KnownFields`] before deserializing.
+use crate::{
+    binding_types::{BindingSpec, Reference},
+    knative_reference::KReference,
+    source_types::{CloudEventOverrides, Destination, SourceSpec},
+};
+use serde::{de::{DeserializeOwned, Error as _}, Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+/// Deserializes `T`, failing on any field that is unknown to `T` or the objects it embeds.
+///
+/// Deserialize `T` directly to ignore unknown fields.
+#[derive(Clone, Debug)]
+pub struct Strict<T>(pub T);
+
+impl<T> Strict<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Strict<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<'de, T: KnownFields + DeserializeOwned> Deserialize<'de> for Strict<T> {
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(de)?;
+        check_fields::<T>(&value, "").map_err(D::Error::custom)?;
+        serde_json::from_value(value).map(Strict).map_err(D::Error::custom)
+    }
+}
+
+/// The serialized fields of an object.
+pub trait KnownFields {
+    /// Every field of the object, including those of flattened objects.
+    const FIELDS: &'static [&'static str];
+
+    /// Check the fields of objects nested within `fields`, whose keys are prefixed with `path`.
+    fn check_nested(_fields: &Map<String, Value>, _path: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Check that `value` has only the fields known to `T`, recursively. Values that are not objects
+/// are left for deserialization to reject.
+pub fn check_fields<T: KnownFields>(value: &Value, path: &str) -> Result<(), String> {
+    let fields = match value.as_object() {
+        Some(fields) => fields,
+        None => return Ok(()),
+    };
+    if let Some(unknown) = fields.keys().find(|k| !T::FIELDS.contains(&k.as_str())) {
+        return Err(format!("unknown field `{path}{unknown}`, expected one of {:?}", T::FIELDS));
+    }
+    T::check_nested(fields, path)
+}
+
+fn check_nested<T: KnownFields>(fields: &Map<String, Value>, key: &str, path: &str) -> Result<(), String> {
+    match fields.get(key) {
+        Some(value) => check_fields::<T>(value, &format!("{path}{key}.")),
+        None => Ok(()),
+    }
+}
+
+impl KnownFields for SourceSpec {
+    const FIELDS: &'static [&'static str] = &["sink", "ceOverrides"];
+
+    fn check_nested(fields: &Map<String, Value>, path: &str) -> Result<(), String> {
+        check_nested::<Destination>(fields, "sink", path)?;
+        check_nested::<CloudEventOverrides>(fields, "ceOverrides", path)
+    }
+}
+
+impl KnownFields for Destination {
+    const FIELDS: &'static [&'static str] = &["ref", "uri", "CACerts", "audience"];
+
+    fn check_nested(fields: &Map<String, Value>, path: &str) -> Result<(), String> {
+        check_nested::<KReference>(fields, "ref", path)
+    }
+}
+
+impl KnownFields for CloudEventOverrides {
+    const FIELDS: &'static [&'static str] = &["extensions", "remove"];
+}
+
+impl KnownFields for KReference {
+    const FIELDS: &'static [&'static str] = &["kind", "namespace", "name", "apiVersion", "group"];
+}
+
+impl KnownFields for BindingSpec {
+    const FIELDS: &'static [&'static str] = &["subject"];
+
+    fn check_nested(fields: &Map<String, Value>, path: &str) -> Result<(), String> {
+        check_nested::<Reference>(fields, "subject", path)
+    }
+}
+
+impl KnownFields for Reference {
+    // includes the fields of the flattened subject
+    const FIELDS: &'static [&'static str] = &["kind", "apiVersion", "namespace", "name", "selector"];
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn rejects_unknown_fields_in_strict_mode() {
+        let spec = json!({
+            "sink": {
+                "ref": {
+                    "apiVersion": "eventing.knative.dev/v1",
+                    "kind": "Broker",
+                    "name": "default",
+                    "nmespace": "default",
+                },
+            },
+        });
+
+        let err = serde_json::from_value::<Strict<SourceSpec>>(spec.clone()).unwrap_err();
+        assert!(err.to_string().starts_with("unknown field `sink.ref.nmespace`"), "{err}");
+
+        // lenient mode ignores the field
+        let lenient = serde_json::from_value::<SourceSpec>(spec).unwrap();
+        assert!(lenient.sink.is_some());
+
+        let binding = json!({ "subject": { "kind": "Deployment", "name": "app", "selecter": {} } });
+        assert!(serde_json::from_value::<Strict<BindingSpec>>(binding.clone()).is_err());
+        assert!(serde_json::from_value::<BindingSpec>(binding).is_ok());
+    }
+
+    #[test]
+    fn accepts_known_fields_in_strict_mode() {
+        let spec = serde_json::from_value::<Strict<SourceSpec>>(json!({
+            "sink": { "uri": "http://sink.default.svc.cluster.local/" },
+            "ceOverrides": { "extensions": { "key": "value" } },
+        })).unwrap();
+        assert!(spec.sink.is_some());
+
+        let binding = serde_json::from_value::<Strict<BindingSpec>>(json!({
+            "subject": { "apiVersion": "apps/v1", "kind": "Deployment", "namespace": "default", "name": "app" },
+        })).unwrap();
+        assert_eq!(binding.into_inner().subject.kind.as_deref(), Some("Deployment"));
+    }
+}
@@ -0,0 +1,148 @@
+//! Helpers for re-reconciling resources when the objects they refer to change.
+use crate::knative_reference::KReference;
+use kube::{api::DynamicObject, runtime::reflector::ObjectRef, Resource};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// The group, kind, namespace and name of a referent. Versions are ignored, so that a reference
+/// matches its referent regardless of the version it is served at.
+type ReferentKey = (String, String, String, String);
+
+fn group(api_version: &str) -> &str {
+    api_version.split_once('/').map_or("", |(group, _)| group)
+}
+
+/// A reverse index from each sink to the sources that reference it, for mapping a changed sink
+/// back to the sources to requeue with [`Controller::watches`].
+///
+/// ```no_run
+/// # use knative::watch::SinkIndex;
+/// # use kube::{api::DynamicObject, runtime::reflector::ObjectRef};
+/// # use k8s_openapi::api::core::v1::Pod as MySource;
+/// # use std::sync::{Arc, Mutex};
+/// let index = Arc::new(Mutex::new(SinkIndex::<MySource>::default()));
+/// // update the index from the reconciler, and requeue sources from the watch of their sinks
+/// let mapper = move |sink: DynamicObject| index.lock().unwrap().sources_for(&sink);
+/// ```
+///
+/// [`Controller::watches`]: kube::runtime::Controller::watches
+pub struct SinkIndex<K: Resource>
+where
+    K::DynamicType: Eq + Hash,
+{
+    sources: HashMap<ReferentKey, HashSet<ObjectRef<K>>>,
+}
+
+impl<K: Resource> Default for SinkIndex<K>
+where
+    K::DynamicType: Eq + Hash,
+{
+    fn default() -> Self {
+        SinkIndex { sources: HashMap::new() }
+    }
+}
+
+impl<K: Resource> SinkIndex<K>
+where
+    K::DynamicType: Eq + Hash + Clone,
+{
+    /// Record the `sink` of the `source`, replacing any sink recorded before. A sink without a
+    /// namespace defaults to the namespace of the source, and one without an api version is
+    /// matched by its group.
+    pub fn set_sink(&mut self, source: ObjectRef<K>, sink: Option<&KReference>) {
+        self.remove(&source);
+        let sink = match sink {
+            Some(sink) => sink,
+            None => return,
+        };
+        let group = match (&sink.api_version, &sink.group) {
+            (Some(api_version), _) => group(api_version),
+            (None, group) => group.as_deref().unwrap_or_default(),
+        };
+        let key = (
+            group.to_string(),
+            sink.kind.clone(),
+            sink.namespace.clone().or_else(|| source.namespace.clone()).unwrap_or_default(),
+            sink.name.clone(),
+        );
+        self.sources.entry(key).or_default().insert(source);
+    }
+
+    /// Forget the sink of the `source`, such as when the source is deleted.
+    pub fn remove(&mut self, source: &ObjectRef<K>) {
+        self.sources.retain(|_, sources| {
+            sources.remove(source);
+            !sources.is_empty()
+        });
+    }
+
+    /// The sources that reference the `sink`.
+    pub fn sources_for(&self, sink: &DynamicObject) -> Vec<ObjectRef<K>> {
+        let types = match &sink.types {
+            Some(types) => types,
+            None => return Vec::new(),
+        };
+        let key = (
+            group(&types.api_version).to_string(),
+            types.kind.clone(),
+            sink.metadata.namespace.clone().unwrap_or_default(),
+            sink.metadata.name.clone().unwrap_or_default(),
+        );
+        self.sources.get(&key)
+            .map(|sources| sources.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mock::read_mock;
+    use k8s_openapi::api::core::v1::Pod;
+
+    fn broker(name: &str, namespace: Option<&str>) -> KReference {
+        KReference {
+            kind: "Broker".into(),
+            namespace: namespace.map(String::from),
+            name: name.into(),
+            api_version: Some("eventing.knative.dev/v1".into()),
+            group: None,
+        }
+    }
+
+    #[test]
+    fn maps_sink_to_sources() {
+        let mut index = SinkIndex::<Pod>::default();
+        let first = ObjectRef::new("first").within("default");
+        let second = ObjectRef::new("second").within("default");
+        index.set_sink(first.clone(), Some(&broker("default", None)));
+        index.set_sink(second.clone(), Some(&broker("default", Some("default"))));
+
+        let default_broker = read_mock::<DynamicObject>("default_broker.yaml");
+        let mut sources = index.sources_for(&default_broker);
+        sources.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(sources, vec![first.clone(), second.clone()]);
+
+        // changing the sink of a source stops requeueing it for the old sink
+        index.set_sink(first, Some(&broker("other", None)));
+        assert_eq!(index.sources_for(&default_broker), vec![second.clone()]);
+
+        index.remove(&second);
+        assert!(index.sources_for(&default_broker).is_empty());
+    }
+
+    #[test]
+    fn maps_group_only_sink_to_sources() {
+        let mut index = SinkIndex::<Pod>::default();
+        let source = ObjectRef::new("source").within("default");
+        let sink = KReference {
+            api_version: None,
+            group: Some("eventing.knative.dev".into()),
+            ..broker("default", None)
+        };
+        index.set_sink(source.clone(), Some(&sink));
+
+        let default_broker = read_mock::<DynamicObject>("default_broker.yaml");
+        assert_eq!(index.sources_for(&default_broker), vec![source]);
+    }
+}